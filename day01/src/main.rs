@@ -1,39 +1,403 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
 use std::env;
+use std::fmt::Debug;
 use std::fs;
+use std::io::{self, BufRead};
+use std::str::FromStr;
 
-fn parse_numbers<'a>(num_strs: impl Iterator<Item = &'a str>) -> Vec<u32> {
+fn parse_numbers<'a, T>(num_strs: impl Iterator<Item = &'a str>) -> Vec<T>
+where
+    T: FromStr,
+    T::Err: Debug,
+{
     num_strs
-        .map(|line| line.parse::<u32>().expect("Failed to parse number"))
+        .map(|line| line.trim().parse::<T>().expect("Failed to parse number"))
         .collect()
 }
 
-fn part1(numbers: &Vec<u32>) -> usize {
-    numbers
-        .windows(2)
-        .filter(|window| window[1] > window[0])
-        .count()
+/// Compares consecutive sliding-window sums as readings arrive, yielding the
+/// index of each new window along with how its sum compares to the last one.
+///
+/// Consecutive windows share all but their first and last readings, so the
+/// sums compare the same way as the reading entering the window and the one
+/// leaving it. That means only the last `size` readings need to be kept, no
+/// sums are ever taken (so nothing can overflow or lose float precision), and
+/// any `PartialOrd` reading type works. A window holding an incomparable
+/// reading such as NaN has no meaningful sum, so comparisons either side of it
+/// are `None`.
+struct WindowComparisons<I, T> {
+    numbers: I,
+    size: usize,
+    recent: VecDeque<T>,
+    /// How many of the `recent` readings don't compare with themselves.
+    incomparable: usize,
+    index: usize,
 }
 
-fn part2(numbers: &Vec<u32>) -> usize {
-    let sums: Vec<u32> = numbers
-        .windows(3)
-        .map(|window| window[0] + window[1] + window[2])
+fn is_incomparable<T: PartialOrd>(number: &T) -> bool {
+    number.partial_cmp(number).is_none()
+}
+
+fn window_comparisons<I, T>(numbers: I, size: usize) -> WindowComparisons<I, T>
+where
+    I: Iterator<Item = T>,
+{
+    assert!(size > 0, "Window size must be at least 1");
+    WindowComparisons {
+        numbers,
+        size,
+        recent: VecDeque::with_capacity(size + 1),
+        incomparable: 0,
+        index: 0,
+    }
+}
+
+impl<I, T> Iterator for WindowComparisons<I, T>
+where
+    I: Iterator<Item = T>,
+    T: PartialOrd,
+{
+    type Item = (usize, Option<Ordering>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let number = self.numbers.next()?;
+            if is_incomparable(&number) {
+                self.incomparable += 1;
+            }
+            self.recent.push_back(number);
+            if self.recent.len() > self.size {
+                // Between them, the old and new windows cover every recent reading
+                let ordering = if self.incomparable > 0 {
+                    None
+                } else {
+                    self.recent.back().unwrap().partial_cmp(&self.recent[0])
+                };
+                let leaving = self.recent.pop_front().unwrap();
+                if is_incomparable(&leaving) {
+                    self.incomparable -= 1;
+                }
+                self.index += 1;
+                return Some((self.index, ordering));
+            }
+        }
+    }
+}
+
+/// The running number of window increases after each new window.
+fn window_increases<T: PartialOrd>(
+    numbers: impl Iterator<Item = T>,
+    size: usize,
+) -> impl Iterator<Item = usize> {
+    window_comparisons(numbers, size).scan(0, |count, (_, ordering)| {
+        if ordering == Some(Ordering::Greater) {
+            *count += 1;
+        }
+        Some(*count)
+    })
+}
+
+fn count_increases<T: PartialOrd>(numbers: impl Iterator<Item = T>, size: usize) -> usize {
+    window_increases(numbers, size).last().unwrap_or(0)
+}
+
+/// A stretch of consecutive window comparisons, by the index of the first
+/// window that took part and the number of comparisons.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Run {
+    start: usize,
+    len: usize,
+}
+
+/// Tracks the longest stretch of consecutive window comparisons matching some
+/// condition.
+#[derive(Default)]
+struct RunTracker {
+    current: Run,
+    longest: Run,
+}
+
+impl RunTracker {
+    fn record(&mut self, index: usize, matched: bool) {
+        if !matched {
+            self.current.len = 0;
+            return;
+        }
+
+        if self.current.len == 0 {
+            self.current.start = index - 1;
+        }
+        self.current.len += 1;
+        if self.current.len > self.longest.len {
+            self.longest = self.current;
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct IncreaseReport {
+    /// Indices of the windows whose sum was larger than the previous window's.
+    increases: Vec<usize>,
+    longest_run: Run,
+}
+
+fn analyze_increases<T: PartialOrd>(
+    numbers: impl Iterator<Item = T>,
+    size: usize,
+) -> IncreaseReport {
+    let mut increases = vec![];
+    let mut runs = RunTracker::default();
+
+    for (index, ordering) in window_comparisons(numbers, size) {
+        let increased = ordering == Some(Ordering::Greater);
+        if increased {
+            increases.push(index);
+        }
+        runs.record(index, increased);
+    }
+
+    IncreaseReport {
+        increases,
+        longest_run: runs.longest,
+    }
+}
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Summary statistics for a depth log. Readings that aren't numbers (NaN) are
+/// left out of the minimum and maximum, and a window holding one has no sum,
+/// so no change into or out of it counts as an increase, decrease or plateau.
+#[derive(Debug, PartialEq)]
+struct DepthProfile {
+    readings: usize,
+    window: usize,
+    /// The first index holding the shallowest reading, and that reading.
+    min: Option<(usize, f64)>,
+    /// The first index holding the deepest reading, and that reading.
+    max: Option<(usize, f64)>,
+    increases: usize,
+    decreases: usize,
+    plateaus: usize,
+    longest_increase: Run,
+    longest_decrease: Run,
+    sparkline: String,
+}
+
+/// Draw the depths as a row of block characters, taller for deeper readings.
+/// Long logs are averaged down to at most `width` characters.
+fn sparkline(depths: &[f64], width: usize) -> String {
+    let readings: Vec<f64> = depths.iter().copied().filter(|d| !d.is_nan()).collect();
+    if readings.is_empty() || width == 0 {
+        return String::new();
+    }
+
+    let bucket_size = readings.len().div_ceil(width);
+    let buckets: Vec<f64> = readings
+        .chunks(bucket_size)
+        .map(|chunk| chunk.iter().sum::<f64>() / chunk.len() as f64)
         .collect();
 
-    sums.windows(2)
-        .filter(|window| window[1] > window[0])
-        .count()
+    let low = buckets.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = buckets.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let top = (SPARK_LEVELS.len() - 1) as f64;
+
+    buckets
+        .iter()
+        .map(|&depth| {
+            let level = if high > low {
+                ((depth - low) / (high - low) * top).round() as usize
+            } else {
+                0
+            };
+            SPARK_LEVELS[level]
+        })
+        .collect()
+}
+
+fn profile_depths(depths: &[f64], window: usize, chart_width: usize) -> DepthProfile {
+    let readings = depths
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|(_, d)| !d.is_nan());
+    let min = readings
+        .clone()
+        .fold(None, |best: Option<(usize, f64)>, (i, d)| match best {
+            Some((_, b)) if b <= d => best,
+            _ => Some((i, d)),
+        });
+    let max = readings.fold(None, |best: Option<(usize, f64)>, (i, d)| match best {
+        Some((_, b)) if b >= d => best,
+        _ => Some((i, d)),
+    });
+
+    let (mut increases, mut decreases, mut plateaus) = (0, 0, 0);
+    let mut rising = RunTracker::default();
+    let mut falling = RunTracker::default();
+    for (index, ordering) in window_comparisons(depths.iter(), window) {
+        match ordering {
+            Some(Ordering::Greater) => increases += 1,
+            Some(Ordering::Less) => decreases += 1,
+            Some(Ordering::Equal) => plateaus += 1,
+            None => {}
+        }
+        rising.record(index, ordering == Some(Ordering::Greater));
+        falling.record(index, ordering == Some(Ordering::Less));
+    }
+
+    DepthProfile {
+        readings: depths.len(),
+        window,
+        min,
+        max,
+        increases,
+        decreases,
+        plateaus,
+        longest_increase: rising.longest,
+        longest_decrease: falling.longest,
+        sparkline: sparkline(depths, chart_width),
+    }
+}
+
+impl DepthProfile {
+    fn extreme_fields(extreme: Option<(usize, f64)>) -> (String, String) {
+        match extreme {
+            Some((index, depth)) => (depth.to_string(), index.to_string()),
+            None => (String::new(), String::new()),
+        }
+    }
+
+    fn to_text(&self) -> String {
+        let (min, min_at) = Self::extreme_fields(self.min);
+        let (max, max_at) = Self::extreme_fields(self.max);
+
+        let mut lines = vec![
+            format!("Readings: {}", self.readings),
+            format!("Window size: {}", self.window),
+            format!("Shallowest: {} at {}", min, min_at),
+            format!("Deepest: {} at {}", max, max_at),
+            format!("Increases: {}", self.increases),
+            format!("Decreases: {}", self.decreases),
+            format!("Plateaus: {}", self.plateaus),
+            format!(
+                "Longest increasing run: {} from window {}",
+                self.longest_increase.len, self.longest_increase.start
+            ),
+            format!(
+                "Longest decreasing run: {} from window {}",
+                self.longest_decrease.len, self.longest_decrease.start
+            ),
+        ];
+        if !self.sparkline.is_empty() {
+            lines.push(self.sparkline.clone());
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /// One `metric,value,position` row per statistic. The sparkline is left
+    /// out, since it's only useful to look at.
+    fn to_csv(&self) -> String {
+        let (min, min_at) = Self::extreme_fields(self.min);
+        let (max, max_at) = Self::extreme_fields(self.max);
+
+        let rows = vec![
+            ("readings", self.readings.to_string(), String::new()),
+            ("window", self.window.to_string(), String::new()),
+            ("min", min, min_at),
+            ("max", max, max_at),
+            ("increases", self.increases.to_string(), String::new()),
+            ("decreases", self.decreases.to_string(), String::new()),
+            ("plateaus", self.plateaus.to_string(), String::new()),
+            (
+                "longest_increase",
+                self.longest_increase.len.to_string(),
+                self.longest_increase.start.to_string(),
+            ),
+            (
+                "longest_decrease",
+                self.longest_decrease.len.to_string(),
+                self.longest_decrease.start.to_string(),
+            ),
+        ];
+
+        let mut output = String::from("metric,value,position\n");
+        for (metric, value, position) in rows {
+            output += &format!("{},{},{}\n", metric, value, position);
+        }
+        output
+    }
+}
+
+fn part1(numbers: &[u32]) -> usize {
+    count_increases(numbers.iter(), 1)
+}
+
+fn part2(numbers: &[u32]) -> usize {
+    count_increases(numbers.iter(), 3)
+}
+
+/// Read depths from stdin as they arrive, printing the running number of
+/// increases for the given window size after each one.
+fn stream_stdin(size: usize) {
+    let stdin = io::stdin();
+    let numbers = stdin.lock().lines().map(|line| {
+        line.expect("Error reading stdin")
+            .trim()
+            .parse::<f64>()
+            .expect("Failed to parse number")
+    });
+
+    for count in window_increases(numbers, size) {
+        println!("{}", count);
+    }
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     let filename = &args[1];
 
+    if filename == "-" {
+        let window = args
+            .get(2)
+            .map_or(1, |s| s.parse().expect("Invalid window size"));
+        stream_stdin(window);
+        return;
+    }
+
     let contents = fs::read_to_string(filename).expect("Error opening file");
 
-    let numbers = parse_numbers(contents.lines());
-    println!("{}", part1(&numbers));
-    println!("{}", part2(&numbers));
+    match args.get(2).map(String::as_str) {
+        Some("report") => {
+            let format = args.get(3).map_or("text", String::as_str);
+            let window = args
+                .get(4)
+                .map_or(1, |s| s.parse().expect("Invalid window size"));
+            let depths: Vec<f64> = parse_numbers(contents.lines());
+            let profile = profile_depths(&depths, window, 80);
+            match format {
+                "text" => print!("{}", profile.to_text()),
+                "csv" => print!("{}", profile.to_csv()),
+                _ => panic!("Unknown report format {}", format),
+            }
+        }
+        Some(window) => {
+            let size = window.parse().expect("Invalid window size");
+            let depths: Vec<f64> = parse_numbers(contents.lines());
+            let report = analyze_increases(depths.iter(), size);
+            println!("Increases: {}", report.increases.len());
+            println!("At windows: {:?}", report.increases);
+            println!(
+                "Longest run: {} increases from window {}",
+                report.longest_run.len, report.longest_run.start
+            );
+        }
+        None => {
+            let numbers = parse_numbers(contents.lines());
+            println!("{}", part1(&numbers));
+            println!("{}", part2(&numbers));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -53,4 +417,75 @@ mod tests {
 
         assert_eq!(part2(&sample), 5);
     }
+
+    #[test]
+    fn streaming_counts() {
+        let sample = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+        let running: Vec<usize> = window_increases(sample.into_iter(), 3).collect();
+        assert_eq!(running, vec![1, 1, 1, 2, 3, 4, 5]);
+        assert_eq!(count_increases(vec![1, 2].into_iter(), 5), 0);
+
+        // Neither [1, NaN] nor [NaN, 2] has a sum to compare
+        assert_eq!(count_increases(vec![1.0, f64::NAN, 2.0].into_iter(), 2), 0);
+        let orderings: Vec<_> =
+            window_comparisons(vec![1.0, f64::NAN, 2.0, 3.0, 4.0].into_iter(), 2)
+                .map(|(_, ordering)| ordering)
+                .collect();
+        assert_eq!(orderings, vec![None, None, Some(Ordering::Greater)]);
+    }
+
+    #[test]
+    fn increase_report() {
+        let sample = vec![199i64, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+        let report = analyze_increases(sample.into_iter(), 3);
+        assert_eq!(report.increases, vec![1, 4, 5, 6, 7]);
+        assert_eq!(report.longest_run, Run { start: 3, len: 4 });
+
+        let readings = vec![10.5, 10.25, f64::NAN, 11.0, 11.5, 12.0];
+        let report = analyze_increases(readings.into_iter(), 1);
+        assert_eq!(report.increases, vec![4, 5]);
+        assert_eq!(report.longest_run, Run { start: 3, len: 2 });
+    }
+
+    #[test]
+    fn depth_profile() {
+        let sample = vec![
+            199.0, 200.0, 208.0, 210.0, 200.0, 207.0, 240.0, 269.0, 260.0, 263.0,
+        ];
+
+        let profile = profile_depths(&sample, 1, 80);
+        assert_eq!(profile.min, Some((0, 199.0)));
+        assert_eq!(profile.max, Some((7, 269.0)));
+        assert_eq!(
+            (profile.increases, profile.decreases, profile.plateaus),
+            (7, 2, 0)
+        );
+        assert_eq!(profile.longest_increase, Run { start: 0, len: 3 });
+        assert_eq!(profile.longest_decrease, Run { start: 3, len: 1 });
+        assert_eq!(profile.sparkline, "▁▁▂▂▁▂▅█▇▇");
+
+        let csv = profile.to_csv();
+        assert!(csv.starts_with("metric,value,position\nreadings,10,\n"));
+        assert!(csv.contains("\nmax,269,7\n"));
+        assert!(profile.to_text().contains("Deepest: 269 at 7\n"));
+
+        let windowed = profile_depths(&sample, 3, 80);
+        assert_eq!(
+            (windowed.increases, windowed.decreases, windowed.plateaus),
+            (5, 1, 1)
+        );
+        assert_eq!(sparkline(&sample, 5).chars().count(), 5);
+
+        // The NaN sits in windows 1 to 3, leaving only window 4 to 5 to compare
+        let gappy = vec![1.0, 2.0, 3.0, f64::NAN, 5.0, 6.0, 7.0, 8.0];
+        let profile = profile_depths(&gappy, 3, 80);
+        assert_eq!((profile.min, profile.max), (Some((0, 1.0)), Some((7, 8.0))));
+        assert_eq!(
+            (profile.increases, profile.decreases, profile.plateaus),
+            (1, 0, 0)
+        );
+        assert_eq!(profile.longest_increase, Run { start: 4, len: 1 });
+    }
 }
@@ -1,76 +1,435 @@
-use regex::Regex;
-use std::{env, fs};
+use script::parse_script;
+use std::{env, error::Error, fmt, fs, num::IntErrorKind, str::FromStr};
 
-const INSTRUCTION_RE_STR: &str = r"(\w+) (\d+)";
+mod script;
 
+#[derive(Clone, Copy, Debug)]
 enum Command {
     Forward,
+    Back,
     Up,
     Down,
 }
 
+/// Where an instruction was written, as a 1-based line and column.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Source {
+    line: usize,
+    column: usize,
+}
+
+#[derive(Clone, Debug)]
 struct Instruction {
     command: Command,
-    value: usize,
+    value: i64,
+    source: Source,
+}
+
+/// What to do when an instruction would take the submarine above the surface.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SurfacePolicy {
+    /// Stop at the surface, as if bumping into it.
+    Clamp,
+    /// Reject the course with `NavigationError::AboveSurface`.
+    Error,
+    /// Carry on with a negative depth, i.e. flying.
+    AllowNegative,
+}
+
+impl FromStr for SurfacePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "clamp" => Ok(SurfacePolicy::Clamp),
+            "error" => Ok(SurfacePolicy::Error),
+            "allow" => Ok(SurfacePolicy::AllowNegative),
+            _ => Err(format!("Unknown surface policy {}", s)),
+        }
+    }
+}
+
+/// Why a course can't be followed, with the instruction at fault by its
+/// 1-based step in the course and where it was written. Steps and lines differ
+/// once blank lines, comments, repeats or macros are involved.
+#[derive(Debug, PartialEq)]
+enum NavigationError {
+    AboveSurface {
+        step: usize,
+        source: Source,
+        depth: i64,
+    },
+    /// A coordinate would no longer fit in an `i64`.
+    Overflow { step: usize, source: Source },
+}
+
+impl fmt::Display for NavigationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NavigationError::AboveSurface {
+                step,
+                source,
+                depth,
+            } => write!(
+                f,
+                "Instruction {} (line {}, column {}) would take the submarine above the surface to depth {}",
+                step, source.line, source.column, depth
+            ),
+            NavigationError::Overflow { step, source } => write!(
+                f,
+                "Instruction {} (line {}, column {}) would take the submarine out of range",
+                step, source.line, source.column
+            ),
+        }
+    }
 }
 
-fn parse_instructions(instruction_text: &str) -> Result<Vec<Instruction>, regex::Error> {
-    let instruction_re = Regex::new(INSTRUCTION_RE_STR)?;
+impl Error for NavigationError {}
 
-    Ok(instruction_text
+impl SurfacePolicy {
+    fn check(self, step: usize, source: Source, depth: i64) -> Result<i64, NavigationError> {
+        match self {
+            _ if depth >= 0 => Ok(depth),
+            SurfacePolicy::Clamp => Ok(0),
+            SurfacePolicy::Error => Err(NavigationError::AboveSurface {
+                step,
+                source,
+                depth,
+            }),
+            SurfacePolicy::AllowNegative => Ok(depth),
+        }
+    }
+}
+
+fn parse_command(word: &str) -> Option<Command> {
+    match word {
+        "forward" => Some(Command::Forward),
+        "back" => Some(Command::Back),
+        "down" => Some(Command::Down),
+        "up" => Some(Command::Up),
+        _ => None,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ParseErrorKind {
+    UnknownCommand(String),
+    MissingValue,
+    InvalidValue(String),
+    /// The value doesn't fit in an `i64`. Values that do fit can still take
+    /// the submarine out of range, which navigation reports as
+    /// `NavigationError::Overflow`.
+    ValueOverflow(String),
+}
+
+/// A problem with a line of plain instructions, numbered from 1.
+#[derive(Debug, PartialEq)]
+struct ParseError {
+    line: usize,
+    kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: ", self.line)?;
+        match &self.kind {
+            ParseErrorKind::UnknownCommand(word) => write!(f, "unknown command {}", word),
+            ParseErrorKind::MissingValue => write!(f, "missing value"),
+            ParseErrorKind::InvalidValue(value) => write!(f, "invalid value {}", value),
+            ParseErrorKind::ValueOverflow(value) => write!(f, "value {} is too large", value),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+fn parse_instruction(line: &str, source: Source) -> Result<Instruction, ParseErrorKind> {
+    let mut words = line.split_ascii_whitespace();
+    let word = words.next().ok_or(ParseErrorKind::MissingValue)?;
+    let command =
+        parse_command(word).ok_or_else(|| ParseErrorKind::UnknownCommand(word.to_string()))?;
+    let value_text = words.next().ok_or(ParseErrorKind::MissingValue)?;
+    if let Some(extra) = words.next() {
+        return Err(ParseErrorKind::InvalidValue(extra.to_string()));
+    }
+    if !value_text.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseErrorKind::InvalidValue(value_text.to_string()));
+    }
+    let value = value_text
+        .parse()
+        .map_err(|e: std::num::ParseIntError| match e.kind() {
+            IntErrorKind::PosOverflow => ParseErrorKind::ValueOverflow(value_text.to_string()),
+            _ => ParseErrorKind::InvalidValue(value_text.to_string()),
+        })?;
+    Ok(Instruction {
+        command,
+        value,
+        source,
+    })
+}
+
+/// Parse one `command value` instruction per line, skipping blank lines.
+fn parse_instructions(instruction_text: &str) -> Result<Vec<Instruction>, ParseError> {
+    instruction_text
         .lines()
-        .map(|line| {
-            let caps = instruction_re.captures(line).expect("Invalid instruction");
-            let command = match caps.get(1).map(|c| c.as_str()) {
-                Some("forward") => Command::Forward,
-                Some("down") => Command::Down,
-                Some("up") => Command::Up,
-                _ => panic!("Invalid instruction"),
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            let source = Source {
+                line: idx + 1,
+                column: line.chars().take_while(|c| c.is_whitespace()).count() + 1,
             };
-            let value = caps
-                .get(2)
-                .map(|val| val.as_str().parse().expect("Invalid value"))
-                .expect("Missing value");
-            Instruction { command, value }
+            parse_instruction(line, source).map_err(|kind| ParseError {
+                line: source.line,
+                kind,
+            })
         })
-        .collect())
+        .collect()
 }
 
-fn part1(instructions: &Vec<Instruction>) -> (usize, usize) {
-    instructions
-        .iter()
-        .fold((0, 0), |(h, d), instruction| match instruction.command {
-            Command::Forward => (h + instruction.value, d),
-            Command::Up => (h, d - instruction.value),
-            Command::Down => (h, d + instruction.value),
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Position {
+    horizontal: i64,
+    depth: i64,
+    aim: i64,
+}
+
+/// A set of rules for how each instruction moves the submarine. Surface checks
+/// are applied by `navigate` after every step, so models don't need to handle
+/// them. Steps give `None` if the arithmetic overflows.
+trait SubmarineModel {
+    fn step(&self, position: Position, instruction: &Instruction) -> Option<Position>;
+}
+
+/// The first reading of the manual: `up` and `down` change depth directly.
+struct DirectModel;
+
+impl SubmarineModel for DirectModel {
+    fn step(&self, position: Position, instruction: &Instruction) -> Option<Position> {
+        let value = instruction.value;
+        Some(match instruction.command {
+            Command::Forward => Position {
+                horizontal: position.horizontal.checked_add(value)?,
+                ..position
+            },
+            Command::Back => Position {
+                horizontal: position.horizontal.checked_sub(value)?,
+                ..position
+            },
+            Command::Up => Position {
+                depth: position.depth.checked_sub(value)?,
+                ..position
+            },
+            Command::Down => Position {
+                depth: position.depth.checked_add(value)?,
+                ..position
+            },
+        })
+    }
+}
+
+/// The corrected reading: `up` and `down` change aim, and moving forward
+/// changes depth by aim.
+struct AimModel;
+
+impl SubmarineModel for AimModel {
+    fn step(&self, position: Position, instruction: &Instruction) -> Option<Position> {
+        let value = instruction.value;
+        let dive = position.aim.checked_mul(value);
+        Some(match instruction.command {
+            Command::Forward => Position {
+                horizontal: position.horizontal.checked_add(value)?,
+                depth: position.depth.checked_add(dive?)?,
+                ..position
+            },
+            Command::Back => Position {
+                horizontal: position.horizontal.checked_sub(value)?,
+                depth: position.depth.checked_sub(dive?)?,
+                ..position
+            },
+            Command::Up => Position {
+                aim: position.aim.checked_sub(value)?,
+                ..position
+            },
+            Command::Down => Position {
+                aim: position.aim.checked_add(value)?,
+                ..position
+            },
         })
+    }
+}
+
+const MODEL_NAMES: [&str; 2] = ["direct", "aim"];
+
+fn model_by_name(name: &str) -> Option<Box<dyn SubmarineModel>> {
+    match name {
+        "direct" => Some(Box::new(DirectModel)),
+        "aim" => Some(Box::new(AimModel)),
+        _ => None,
+    }
+}
+
+/// Apply one instruction under the model, then the surface policy.
+fn advance(
+    model: &dyn SubmarineModel,
+    position: Position,
+    (idx, instruction): (usize, &Instruction),
+    policy: SurfacePolicy,
+) -> Result<Position, NavigationError> {
+    let (step, source) = (idx + 1, instruction.source);
+    let next = model
+        .step(position, instruction)
+        .ok_or(NavigationError::Overflow { step, source })?;
+    Ok(Position {
+        depth: policy.check(step, source, next.depth)?,
+        ..next
+    })
 }
 
-fn part2(instructions: &Vec<Instruction>) -> (usize, usize, usize) {
+fn navigate(
+    model: &dyn SubmarineModel,
+    instructions: &[Instruction],
+    policy: SurfacePolicy,
+) -> Result<Position, NavigationError> {
     instructions
         .iter()
-        .fold((0, 0, 0), |(h, d, a), instruction| {
-            match instruction.command {
-                Command::Forward => (h + instruction.value, d + a * instruction.value, a),
-                Command::Up => (h, d, a - instruction.value),
-                Command::Down => (h, d, a + instruction.value),
-            }
+        .enumerate()
+        .try_fold(Position::default(), |position, step| {
+            advance(model, position, step, policy)
         })
 }
 
-fn main() -> Result<(), regex::Error> {
+/// Every position the submarine passes through, starting at the origin and
+/// followed by the position after each instruction.
+fn trace(
+    model: &dyn SubmarineModel,
+    instructions: &[Instruction],
+    policy: SurfacePolicy,
+) -> Result<Vec<Position>, NavigationError> {
+    let mut course = vec![Position::default()];
+    for step in instructions.iter().enumerate() {
+        let next = advance(model, *course.last().unwrap(), step, policy)?;
+        course.push(next);
+    }
+    Ok(course)
+}
+
+fn course_csv(course: &[Position]) -> String {
+    let mut output = String::from("step,horizontal,depth,aim\n");
+    for (step, position) in course.iter().enumerate() {
+        output += &format!(
+            "{},{},{},{}\n",
+            step, position.horizontal, position.depth, position.aim
+        );
+    }
+    output
+}
+
+const SVG_WIDTH: usize = 800;
+const SVG_HEIGHT: usize = 400;
+
+/// Plot the course as a side view, with depth increasing down the page and the
+/// surface drawn as a blue line. The two axes are stretched independently to
+/// fill the image, since depths can be orders of magnitude larger than
+/// horizontal distances.
+fn course_svg(course: &[Position]) -> String {
+    let min_h = course.iter().map(|p| p.horizontal).min().unwrap_or(0);
+    let max_h = course.iter().map(|p| p.horizontal).max().unwrap_or(0);
+    let min_d = course.iter().map(|p| p.depth).min().unwrap_or(0).min(0);
+    let max_d = course.iter().map(|p| p.depth).max().unwrap_or(0);
+    let view_width = max_h.saturating_sub(min_h).max(1);
+    let view_height = max_d.saturating_sub(min_d).max(1);
+
+    let points: Vec<String> = course
+        .iter()
+        .map(|p| format!("{},{}", p.horizontal, p.depth))
+        .collect();
+
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" "#,
+            r#"viewBox="{} {} {} {}" preserveAspectRatio="none">"#,
+            "\n",
+            r#"  <line x1="{}" y1="0" x2="{}" y2="0" stroke="steelblue" "#,
+            r#"vector-effect="non-scaling-stroke"/>"#,
+            "\n",
+            r#"  <polyline points="{}" fill="none" stroke="black" "#,
+            r#"vector-effect="non-scaling-stroke"/>"#,
+            "\n</svg>\n"
+        ),
+        SVG_WIDTH,
+        SVG_HEIGHT,
+        min_h,
+        min_d,
+        view_width,
+        view_height,
+        min_h,
+        min_h.saturating_add(view_width),
+        points.join(" ")
+    )
+}
+
+fn part1(
+    instructions: &[Instruction],
+    policy: SurfacePolicy,
+) -> Result<(i64, i64), NavigationError> {
+    let end = navigate(&DirectModel, instructions, policy)?;
+    Ok((end.horizontal, end.depth))
+}
+
+fn part2(
+    instructions: &[Instruction],
+    policy: SurfacePolicy,
+) -> Result<(i64, i64, i64), NavigationError> {
+    let end = navigate(&AimModel, instructions, policy)?;
+    Ok((end.horizontal, end.depth, end.aim))
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = env::args().collect();
     let filename = &args[1];
+    let policy: SurfacePolicy = args
+        .get(2)
+        .map_or(Ok(SurfacePolicy::Error), |p| p.parse())?;
 
     let contents = fs::read_to_string(filename).expect("Error opening file");
 
-    let instructions = parse_instructions(&contents)?;
+    let instructions = if filename.ends_with(".mission") {
+        parse_script(&contents)?
+    } else {
+        parse_instructions(&contents)?
+    };
+
+    if let Some(name) = args.get(3) {
+        let model = model_by_name(name).ok_or_else(|| {
+            format!(
+                "Unknown model {}, expected one of {}",
+                name,
+                MODEL_NAMES.join(", ")
+            )
+        })?;
 
-    let (x, y) = part1(&instructions);
-    println!("Part 1: {}", x * y);
+        if let Some(output) = args.get(4) {
+            let course = trace(model.as_ref(), &instructions, policy)?;
+            let contents = if output.ends_with(".svg") {
+                course_svg(&course)
+            } else {
+                course_csv(&course)
+            };
+            fs::write(output, contents)?;
+            return Ok(());
+        }
+
+        let end = navigate(model.as_ref(), &instructions, policy)?;
+        println!("{}: {}", name, end.horizontal as i128 * end.depth as i128);
+        return Ok(());
+    }
 
-    let (x2, y2, _) = part2(&instructions);
-    println!("Part 2: {}", x2 * y2);
+    let (x, y) = part1(&instructions, policy)?;
+    println!("Part 1: {}", x as i128 * y as i128);
+
+    let (x2, y2, _) = part2(&instructions, policy)?;
+    println!("Part 2: {}", x2 as i128 * y2 as i128);
 
     Ok(())
 }
@@ -83,17 +442,169 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        let instructions = parse_instructions(SAMPLE).expect("Failed to parse regex");
+        let instructions = parse_instructions(SAMPLE).unwrap();
 
-        assert_eq!(part1(&instructions), (15, 10));
+        assert_eq!(part1(&instructions, SurfacePolicy::Error), Ok((15, 10)));
     }
 
     #[test]
     fn part2_example() {
-        let instructions = parse_instructions(SAMPLE).expect("Failed to parse regex");
+        let instructions = parse_instructions(SAMPLE).unwrap();
 
-        let (x, y, _) = part2(&instructions);
+        let (x, y, _) = part2(&instructions, SurfacePolicy::Error).unwrap();
 
         assert_eq!((x, y), (15, 60));
     }
+
+    #[test]
+    fn surface_policies() {
+        let instructions = parse_instructions("down 2\n\n  up 5\nforward 1\ndown 1").unwrap();
+
+        let error = part1(&instructions, SurfacePolicy::Error).unwrap_err();
+        assert_eq!(
+            error,
+            NavigationError::AboveSurface {
+                step: 2,
+                source: Source { line: 3, column: 3 },
+                depth: -3
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Instruction 2 (line 3, column 3) would take the submarine above the surface to depth -3"
+        );
+        assert_eq!(part1(&instructions, SurfacePolicy::Clamp), Ok((1, 1)));
+        assert_eq!(
+            part1(&instructions, SurfacePolicy::AllowNegative),
+            Ok((1, -2))
+        );
+
+        // Aim may point upwards freely, only the depth is checked
+        assert_eq!(
+            part2(&instructions, SurfacePolicy::Error),
+            Err(NavigationError::AboveSurface {
+                step: 3,
+                source: Source { line: 4, column: 1 },
+                depth: -3
+            })
+        );
+        assert_eq!(part2(&instructions, SurfacePolicy::Clamp), Ok((1, 0, -2)));
+        assert_eq!(
+            part2(&instructions, SurfacePolicy::AllowNegative),
+            Ok((1, -3, -2))
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let deep = parse_instructions("down 9223372036854775807\nforward 2").unwrap();
+        assert_eq!(part1(&deep, SurfacePolicy::Error), Ok((2, i64::MAX)));
+        assert_eq!(
+            part2(&deep, SurfacePolicy::Error),
+            Err(NavigationError::Overflow {
+                step: 2,
+                source: Source { line: 2, column: 1 }
+            })
+        );
+
+        let steep =
+            parse_instructions("forward 3037000500\ndown 3037000500\nforward 3037000500").unwrap();
+        assert_eq!(
+            part2(&steep, SurfacePolicy::Error),
+            Err(NavigationError::Overflow {
+                step: 3,
+                source: Source { line: 3, column: 1 }
+            })
+        );
+        assert_eq!(
+            part1(
+                &parse_instructions("up 1\nup 9223372036854775807").unwrap(),
+                SurfacePolicy::Clamp
+            ),
+            Ok((0, 0))
+        );
+    }
+
+    #[test]
+    fn models_by_name() {
+        let instructions = parse_instructions(SAMPLE).unwrap();
+
+        let ends: Vec<Position> = MODEL_NAMES
+            .iter()
+            .map(|name| {
+                let model = model_by_name(name).unwrap();
+                navigate(model.as_ref(), &instructions, SurfacePolicy::Error).unwrap()
+            })
+            .collect();
+        assert_eq!((ends[0].horizontal, ends[0].depth), (15, 10));
+        assert_eq!(
+            (ends[1].horizontal, ends[1].depth, ends[1].aim),
+            (15, 60, 10)
+        );
+        assert!(model_by_name("warp").is_none());
+    }
+
+    #[test]
+    fn course_exports() {
+        let instructions = parse_instructions(SAMPLE).unwrap();
+        let course = trace(&AimModel, &instructions, SurfacePolicy::Error).unwrap();
+
+        assert_eq!(course.len(), 7);
+        assert_eq!(course[0], Position::default());
+        assert_eq!(
+            course.last(),
+            Some(&navigate(&AimModel, &instructions, SurfacePolicy::Error).unwrap())
+        );
+
+        let csv = course_csv(&course);
+        assert!(csv.starts_with("step,horizontal,depth,aim\n0,0,0,0\n1,5,0,0\n2,5,0,5\n"));
+        assert!(csv.ends_with("6,15,60,10\n"));
+
+        let svg = course_svg(&course);
+        assert!(svg.contains(r#"viewBox="0 0 15 60""#));
+        assert!(svg.contains(r#"points="0,0 5,0 5,0 13,40 13,40 13,40 15,60""#));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |text| parse_instructions(text).unwrap_err();
+
+        assert_eq!(
+            parse_instructions("\nforward 5\n\n up  3 \n")
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            error("forward 5\nsideways 2"),
+            ParseError {
+                line: 2,
+                kind: ParseErrorKind::UnknownCommand("sideways".to_string())
+            }
+        );
+        assert_eq!(error("down").kind, ParseErrorKind::MissingValue);
+        assert_eq!(
+            error("down -4").kind,
+            ParseErrorKind::InvalidValue("-4".to_string())
+        );
+        assert_eq!(
+            error("up 1 2").kind,
+            ParseErrorKind::InvalidValue("2".to_string())
+        );
+        assert_eq!(
+            error("up 9223372036854775808").kind,
+            ParseErrorKind::ValueOverflow("9223372036854775808".to_string())
+        );
+
+        // The largest accepted value parses, and overflow during navigation
+        // is an error rather than a panic
+        let largest = parse_instructions("forward 9223372036854775807\nforward 1").unwrap();
+        assert_eq!(
+            part1(&largest, SurfacePolicy::Error),
+            Err(NavigationError::Overflow {
+                step: 2,
+                source: Source { line: 2, column: 1 }
+            })
+        );
+    }
 }
@@ -0,0 +1,364 @@
+//! Mission scripts: the plain instruction format plus `#` comments, blank
+//! lines, `repeat N { ... }` blocks and named macros.
+//!
+//! ```text
+//! # Dive, then skim along
+//! macro dive {
+//!     down 5
+//!     forward 2
+//! }
+//!
+//! repeat 3 { dive }
+//! back 4 # overshot
+//! ```
+//!
+//! Macros must be defined before they're used, and a macro's body can use any
+//! macros defined before it. Everything is expanded into a flat instruction
+//! list while parsing, with each instruction keeping the position of the
+//! command that was written, so repeats and macros share theirs.
+
+use crate::{parse_command, Instruction, Source};
+use std::{collections::HashMap, error::Error, fmt, str::FromStr};
+
+#[derive(Debug, PartialEq)]
+pub enum ScriptErrorKind {
+    /// A word that isn't a command, keyword or defined macro.
+    UnknownWord(String),
+    InvalidNumber(String),
+    /// A token that can't appear where it was found, such as a stray `}`.
+    Unexpected(String),
+    /// The script ended while a value or block was still expected.
+    UnexpectedEnd,
+    DuplicateMacro(String),
+    /// Expanding repeats and macros would give more than `MAX_INSTRUCTIONS`.
+    TooLong,
+}
+
+/// The most instructions a script may expand to.
+pub const MAX_INSTRUCTIONS: usize = 10_000_000;
+
+/// A problem with a mission script, at a 1-based line and column.
+#[derive(Debug, PartialEq)]
+pub struct ScriptError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ScriptErrorKind,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}, column {}: ", self.line, self.column)?;
+        match &self.kind {
+            ScriptErrorKind::UnknownWord(word) => write!(f, "unknown command or macro {}", word),
+            ScriptErrorKind::InvalidNumber(value) => write!(f, "invalid number {}", value),
+            ScriptErrorKind::Unexpected(token) => write!(f, "unexpected {}", token),
+            ScriptErrorKind::UnexpectedEnd => write!(f, "unexpected end of script"),
+            ScriptErrorKind::DuplicateMacro(name) => write!(f, "macro {} is already defined", name),
+            ScriptErrorKind::TooLong => write!(
+                f,
+                "script expands to more than {} instructions",
+                MAX_INSTRUCTIONS
+            ),
+        }
+    }
+}
+
+impl Error for ScriptError {}
+
+#[derive(Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+fn tokenize(script: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    for (line_idx, line) in script.lines().enumerate() {
+        let code = line.split('#').next().unwrap_or("");
+        let mut word_start: Option<usize> = None;
+        for (idx, c) in code
+            .char_indices()
+            .chain(std::iter::once((code.len(), ' ')))
+        {
+            let is_brace = c == '{' || c == '}';
+            if c.is_whitespace() || is_brace {
+                if let Some(start) = word_start.take() {
+                    tokens.push(Token {
+                        text: &code[start..idx],
+                        line: line_idx + 1,
+                        column: code[..start].chars().count() + 1,
+                    });
+                }
+                if is_brace {
+                    tokens.push(Token {
+                        text: &code[idx..idx + 1],
+                        line: line_idx + 1,
+                        column: code[..idx].chars().count() + 1,
+                    });
+                }
+            } else if word_start.is_none() {
+                word_start = Some(idx);
+            }
+        }
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+    macros: HashMap<&'a str, Vec<Instruction>>,
+    /// Where to report errors about the end of the script.
+    end: (usize, usize),
+}
+
+impl<'a> Parser<'a> {
+    fn error(token: &Token, kind: ScriptErrorKind) -> ScriptError {
+        ScriptError {
+            line: token.line,
+            column: token.column,
+            kind,
+        }
+    }
+
+    fn next(&mut self) -> Result<Token<'a>, ScriptError> {
+        let token = self.tokens.get(self.pos).copied().ok_or(ScriptError {
+            line: self.end.0,
+            column: self.end.1,
+            kind: ScriptErrorKind::UnexpectedEnd,
+        })?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn number<T: FromStr>(&mut self) -> Result<T, ScriptError> {
+        let token = self.next()?;
+        let invalid = || {
+            Self::error(
+                &token,
+                ScriptErrorKind::InvalidNumber(token.text.to_string()),
+            )
+        };
+        if !token.text.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        token.text.parse().map_err(|_| invalid())
+    }
+
+    /// Make sure adding `extra` instructions, or `None` if that count itself
+    /// overflowed, keeps the script within `MAX_INSTRUCTIONS`.
+    fn check_length(
+        token: &Token,
+        instructions: &[Instruction],
+        extra: Option<usize>,
+    ) -> Result<(), ScriptError> {
+        match extra.and_then(|extra| extra.checked_add(instructions.len())) {
+            Some(total) if total <= MAX_INSTRUCTIONS => Ok(()),
+            _ => Err(Self::error(token, ScriptErrorKind::TooLong)),
+        }
+    }
+
+    fn open_block(&mut self) -> Result<(), ScriptError> {
+        let token = self.next()?;
+        if token.text == "{" {
+            Ok(())
+        } else {
+            Err(Self::error(
+                &token,
+                ScriptErrorKind::Unexpected(token.text.to_string()),
+            ))
+        }
+    }
+
+    /// Parse statements until the end of the script, or the closing brace of
+    /// the current block when `in_block` is set.
+    fn statements(&mut self, in_block: bool) -> Result<Vec<Instruction>, ScriptError> {
+        let mut instructions = vec![];
+        loop {
+            let token = match self.tokens.get(self.pos) {
+                None if !in_block => return Ok(instructions),
+                _ => self.next()?,
+            };
+
+            match token.text {
+                "}" if in_block => return Ok(instructions),
+                "repeat" => {
+                    let count: usize = self.number()?;
+                    self.open_block()?;
+                    let body = self.statements(true)?;
+                    let expanded = count.checked_mul(body.len());
+                    Self::check_length(&token, &instructions, expanded)?;
+                    // Cycling rather than looping `count` times, so empty
+                    // bodies cost nothing however large the count
+                    instructions.extend(body.iter().cloned().cycle().take(count * body.len()));
+                }
+                "macro" => {
+                    let name = self.next()?;
+                    if self.macros.contains_key(name.text) {
+                        return Err(Self::error(
+                            &name,
+                            ScriptErrorKind::DuplicateMacro(name.text.to_string()),
+                        ));
+                    }
+                    let reserved = ["{", "}", "repeat", "macro"];
+                    if reserved.contains(&name.text) || parse_command(name.text).is_some() {
+                        return Err(Self::error(
+                            &name,
+                            ScriptErrorKind::Unexpected(name.text.to_string()),
+                        ));
+                    }
+                    self.open_block()?;
+                    let body = self.statements(true)?;
+                    self.macros.insert(name.text, body);
+                }
+                word => {
+                    if let Some(command) = parse_command(word) {
+                        let value = self.number()?;
+                        Self::check_length(&token, &instructions, Some(1))?;
+                        let source = Source {
+                            line: token.line,
+                            column: token.column,
+                        };
+                        instructions.push(Instruction {
+                            command,
+                            value,
+                            source,
+                        });
+                    } else if let Some(body) = self.macros.get(word) {
+                        Self::check_length(&token, &instructions, Some(body.len()))?;
+                        instructions.extend(body.iter().cloned());
+                    } else if word == "{" || word == "}" {
+                        return Err(Self::error(
+                            &token,
+                            ScriptErrorKind::Unexpected(word.to_string()),
+                        ));
+                    } else {
+                        return Err(Self::error(
+                            &token,
+                            ScriptErrorKind::UnknownWord(word.to_string()),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn parse_script(script: &str) -> Result<Vec<Instruction>, ScriptError> {
+    let line_count = script.lines().count();
+    let last_line = script.lines().last().unwrap_or("");
+    let mut parser = Parser {
+        tokens: tokenize(script),
+        pos: 0,
+        macros: HashMap::new(),
+        end: (line_count.max(1), last_line.chars().count() + 1),
+    };
+    parser.statements(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{navigate, AimModel, DirectModel, NavigationError, SurfacePolicy};
+
+    #[test]
+    fn plain_instructions_are_scripts() {
+        let plain = parse_script(include_str!("sample")).unwrap();
+        let end = navigate(&AimModel, &plain, SurfacePolicy::Error).unwrap();
+        assert_eq!((end.horizontal, end.depth), (15, 60));
+    }
+
+    #[test]
+    fn macros_and_repeats() {
+        let script = "
+            # The sample course, written compactly
+            macro glide { forward 8 }
+            macro dive {
+                down 5
+            }
+
+            forward 5 # set off
+            dive
+            glide
+            repeat 2 { up 3 down 8 }
+            back 1
+            forward 5
+        ";
+        let instructions = parse_script(script).unwrap();
+        assert_eq!(instructions.len(), 9);
+        let end = navigate(&AimModel, &instructions, SurfacePolicy::Error).unwrap();
+        assert_eq!((end.horizontal, end.depth, end.aim), (17, 100, 15));
+    }
+
+    #[test]
+    fn navigation_errors_point_at_the_script() {
+        let script = "macro rise {\n  forward 1 up 2\n}\ndown 3\nrepeat 2 { rise }";
+        let instructions = parse_script(script).unwrap();
+        assert_eq!(
+            navigate(&DirectModel, &instructions, SurfacePolicy::Error),
+            Err(NavigationError::AboveSurface {
+                step: 5,
+                source: Source {
+                    line: 2,
+                    column: 13
+                },
+                depth: -1
+            })
+        );
+    }
+
+    #[test]
+    fn empty_repeats() {
+        let script = "repeat 18446744073709551615 { }\nrepeat 3 { repeat 99999999999 { } up 1 }";
+        let instructions = parse_script(script).unwrap();
+        assert_eq!(instructions.len(), 3);
+    }
+
+    #[test]
+    fn error_positions() {
+        let error = |script| parse_script(script).unwrap_err();
+
+        assert_eq!(
+            error("forward 1\n  sideways 2"),
+            ScriptError {
+                line: 2,
+                column: 3,
+                kind: ScriptErrorKind::UnknownWord("sideways".to_string())
+            }
+        );
+        assert_eq!(
+            error("down -4").kind,
+            ScriptErrorKind::InvalidNumber("-4".to_string())
+        );
+        assert_eq!(
+            error("repeat 2 {\n  up 1\n").kind,
+            ScriptErrorKind::UnexpectedEnd
+        );
+        assert_eq!(error("up 1 }").column, 6);
+        assert_eq!(
+            error("macro m { up 1 }\nmacro m { down 1 }").kind,
+            ScriptErrorKind::DuplicateMacro("m".to_string())
+        );
+        assert_eq!(
+            error("macro up { down 1 }").kind,
+            ScriptErrorKind::Unexpected("up".to_string())
+        );
+        assert_eq!(
+            error("repeat 100000 {\n  repeat 100000 { repeat 100000 { up 1 } }\n}"),
+            ScriptError {
+                line: 2,
+                column: 3,
+                kind: ScriptErrorKind::TooLong
+            }
+        );
+        assert_eq!(
+            error("macro m { up 1 down 1 }\nrepeat 5000001 { m }").kind,
+            ScriptErrorKind::TooLong
+        );
+        assert_eq!(
+            error("macro m { m }").kind,
+            ScriptErrorKind::UnknownWord("m".to_string())
+        );
+    }
+}
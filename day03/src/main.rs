@@ -1,114 +1,532 @@
-use std::{env, fs};
+use std::{env, error::Error, fmt, fs, time::Instant};
 
-fn one_counts(numbers: &Vec<&str>) -> Vec<usize> {
-    let mut ones: Vec<usize> = vec![];
-    for _ in numbers[0].chars() {
-        ones.push(0);
+/// The widest report line that fits in a `u64`.
+const MAX_WIDTH: usize = 64;
+
+/// A diagnostic report: every line is a binary number of the same width.
+#[derive(Debug)]
+struct Report {
+    width: usize,
+    numbers: Vec<u64>,
+}
+
+#[derive(Debug, PartialEq)]
+enum ParseErrorKind {
+    Empty,
+    InvalidDigit(char),
+    /// The line's width differs from the first line's.
+    WidthMismatch(usize),
+    TooWide(usize),
+}
+
+/// A problem with a report line, numbered from 1.
+#[derive(Debug, PartialEq)]
+struct ParseError {
+    line: usize,
+    kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: ", self.line)?;
+        match self.kind {
+            ParseErrorKind::Empty => write!(f, "empty report"),
+            ParseErrorKind::InvalidDigit(c) => write!(f, "invalid binary digit {:?}", c),
+            ParseErrorKind::WidthMismatch(width) => {
+                write!(f, "expected {} bits like the first line", width)
+            }
+            ParseErrorKind::TooWide(width) => {
+                write!(
+                    f,
+                    "{} bits is wider than the {} supported",
+                    width, MAX_WIDTH
+                )
+            }
+        }
     }
-    for number in numbers.iter() {
-        for (idx, digit) in number.chars().enumerate() {
-            let count = ones.get(idx).unwrap_or(&0);
-            ones[idx] = if digit == '1' { *count + 1 } else { *count }
+}
+
+impl Error for ParseError {}
+
+impl Report {
+    fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut numbers = vec![];
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |kind| ParseError {
+                line: idx + 1,
+                kind,
+            };
+
+            let expected = *width.get_or_insert(line.len());
+            if line.len() != expected {
+                return Err(error(ParseErrorKind::WidthMismatch(expected)));
+            }
+            if expected > MAX_WIDTH {
+                return Err(error(ParseErrorKind::TooWide(expected)));
+            }
+
+            let mut number = 0;
+            for c in line.chars() {
+                let digit = match c {
+                    '0' => 0,
+                    '1' => 1,
+                    _ => return Err(error(ParseErrorKind::InvalidDigit(c))),
+                };
+                number = number << 1 | digit;
+            }
+            numbers.push(number);
+        }
+
+        match width {
+            Some(width) => Ok(Self { width, numbers }),
+            None => Err(ParseError {
+                line: 1,
+                kind: ParseErrorKind::Empty,
+            }),
         }
     }
 
-    ones
+    /// The mask selecting bit `idx`, counting from the leftmost digit.
+    fn bit(&self, idx: usize) -> u64 {
+        1 << (self.width - 1 - idx)
+    }
+
+    /// A mask covering every bit in the report's width.
+    fn mask(&self) -> u64 {
+        u64::MAX >> (MAX_WIDTH - self.width)
+    }
 }
 
-fn part1(numbers: &Vec<&str>) -> usize {
-    let threshold = numbers.len() / 2;
-    let ones: Vec<usize> = one_counts(numbers);
+/// The number of ones in each bit position, from the leftmost digit.
+fn one_counts(report: &Report, numbers: &[u64]) -> Vec<usize> {
+    let mut ones = vec![0; report.width];
+    for &number in numbers.iter() {
+        let mut remaining = number;
+        while remaining != 0 {
+            let position = remaining.trailing_zeros() as usize;
+            ones[report.width - 1 - position] += 1;
+            remaining &= remaining - 1;
+        }
+    }
 
-    let (gb, eb) = ones
+    ones
+}
+
+/// The gamma and epsilon rates: the most and least common bit in each
+/// position. Ties count as zero in gamma, and so one in epsilon.
+fn power_rates(report: &Report, ones: &[usize]) -> (u64, u64) {
+    let threshold = report.numbers.len() / 2;
+    let gamma = ones
         .iter()
-        .fold((String::new(), String::new()), |(gs, es), &count| {
-            if count > threshold {
-                (gs + "1", es + "0")
-            } else {
-                (gs + "0", es + "1")
+        .enumerate()
+        .filter(|(_, &count)| count > threshold)
+        .fold(0, |acc, (idx, _)| acc | report.bit(idx));
+    (gamma, !gamma & report.mask())
+}
+
+fn part1(report: &Report) -> u128 {
+    let (gamma, epsilon) = power_rates(report, &one_counts(report, &report.numbers));
+    gamma as u128 * epsilon as u128
+}
+
+/// Which bit value a rating keeps at each position, given how common it is
+/// among the remaining candidates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Commonality {
+    Most,
+    Least,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct BitCriterion {
+    keep: Commonality,
+    /// The bit to keep when ones and zeros are equally common.
+    tie_break: bool,
+}
+
+const OXYGEN: BitCriterion = BitCriterion {
+    keep: Commonality::Most,
+    tie_break: true,
+};
+
+const CO2: BitCriterion = BitCriterion {
+    keep: Commonality::Least,
+    tie_break: false,
+};
+
+impl BitCriterion {
+    /// Whether to keep candidates with a one in a position where `ones` of
+    /// `total` candidates have one.
+    fn keeps_ones(self, ones: usize, total: usize) -> bool {
+        let zeros = total - ones;
+        if ones == zeros {
+            return self.tie_break;
+        }
+        match self.keep {
+            Commonality::Most => ones > zeros,
+            Commonality::Least => ones < zeros,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum RatingError {
+    /// No candidates were left after filtering on the given bit.
+    NoCandidates { bit: usize },
+    /// Every bit was checked and more than one candidate remained.
+    OutOfBits { remaining: usize },
+}
+
+impl fmt::Display for RatingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RatingError::NoCandidates { bit } => {
+                write!(f, "no candidates left after checking bit {}", bit)
             }
-        });
+            RatingError::OutOfBits { remaining } => {
+                write!(f, "{} candidates left after checking every bit", remaining)
+            }
+        }
+    }
+}
 
-    let gamma = usize::from_str_radix(&gb, 2).expect("Invalid gamma binary");
-    let epsilon = usize::from_str_radix(&eb, 2).expect("Invalid epsilon binary");
+impl Error for RatingError {}
 
-    println!("{}, {}", gamma, epsilon);
+/// Narrow the report down one bit at a time, from the left, until a single
+/// number matches the criterion.
+fn rate(report: &Report, criterion: BitCriterion) -> Result<u64, RatingError> {
+    filter_from(report, report.numbers.clone(), 0, criterion)
+}
+
+/// Carry on filtering candidates that already agree on the bits before
+/// `start`.
+fn filter_from(
+    report: &Report,
+    mut candidates: Vec<u64>,
+    start: usize,
+    criterion: BitCriterion,
+) -> Result<u64, RatingError> {
+    for idx in start..report.width {
+        if candidates.len() == 1 {
+            break;
+        }
+        let bit = report.bit(idx);
+        let ones = candidates.iter().filter(|&&n| n & bit != 0).count();
+        let keep = if criterion.keeps_ones(ones, candidates.len()) {
+            bit
+        } else {
+            0
+        };
+        candidates.retain(|&n| n & bit == keep);
+        if candidates.is_empty() {
+            return Err(RatingError::NoCandidates { bit: idx });
+        }
+    }
+
+    match candidates[..] {
+        [rating] => Ok(rating),
+        _ => Err(RatingError::OutOfBits {
+            remaining: candidates.len(),
+        }),
+    }
+}
+
+/// Deeper tries rarely help, since by then few numbers share each prefix,
+/// and the counts would take too much memory.
+const MAX_TRIE_DEPTH: usize = 22;
 
-    gamma * epsilon
+/// The top levels of a binary trie over the report, storing how many numbers
+/// share each prefix. Level `d` has a count for every `d`-bit prefix, so it's
+/// stored as a flat array rather than linked nodes.
+struct PrefixTrie {
+    depth: usize,
+    levels: Vec<Vec<usize>>,
 }
 
-fn part2(numbers: &Vec<&str>) -> usize {
-    let mut oxygen_candidates = numbers.clone();
-    let mut oxygen_check_bit: usize = 0;
-    let oxygen_str = loop {
-        if oxygen_candidates.len() == 1 {
-            break oxygen_candidates.get(0).unwrap();
+impl PrefixTrie {
+    /// Count the numbers under every node in one pass over the report. The
+    /// trie is just deep enough that each bottom node holds about one number.
+    fn new(report: &Report) -> Self {
+        let needed = (usize::BITS - report.numbers.len().leading_zeros()) as usize;
+        let depth = needed.min(report.width).min(MAX_TRIE_DEPTH);
+
+        let mut bottom = vec![0; 1 << depth];
+        for &number in report.numbers.iter() {
+            bottom[Self::prefix(report, number, depth)] += 1;
         }
-        let ones: usize = oxygen_candidates.iter().fold(0, |acc, num| {
-            if num
-                .chars()
-                .nth(oxygen_check_bit)
-                .expect("Oxygen check bit past length")
-                == '1'
-            {
-                acc + 1
-            } else {
-                acc
+        let mut levels = vec![bottom];
+        for _ in 0..depth {
+            let parents = levels[0].chunks(2).map(|pair| pair[0] + pair[1]).collect();
+            levels.insert(0, parents);
+        }
+
+        Self { depth, levels }
+    }
+
+    /// The first `len` bits of a number.
+    fn prefix(report: &Report, number: u64, len: usize) -> usize {
+        number.checked_shr((report.width - len) as u32).unwrap_or(0) as usize
+    }
+
+    /// The same rating as `rate`. The counts decide each bit down to the
+    /// bottom of the trie, then the few numbers left are filtered as usual.
+    fn rate(&self, report: &Report, criterion: BitCriterion) -> Result<u64, RatingError> {
+        let mut prefix = 0;
+        let mut decided = 0;
+        while decided < self.depth {
+            let total = self.levels[decided][prefix];
+            if total == 1 {
+                break;
             }
-        });
-        let threshold: f32 = oxygen_candidates.len() as f32 / 2.0;
-        let mcb = if ones as f32 >= threshold { '1' } else { '0' };
-        oxygen_candidates = oxygen_candidates
-            .into_iter()
-            .filter(|num| num.chars().nth(oxygen_check_bit).unwrap() == mcb)
+            let ones = self.levels[decided + 1][prefix * 2 + 1];
+            let keeps_ones = criterion.keeps_ones(ones, total);
+            let kept = if keeps_ones { ones } else { total - ones };
+            if kept == 0 {
+                return Err(RatingError::NoCandidates { bit: decided });
+            }
+            prefix = prefix * 2 + keeps_ones as usize;
+            decided += 1;
+        }
+
+        let candidates = report
+            .numbers
+            .iter()
+            .copied()
+            .filter(|&n| Self::prefix(report, n, decided) == prefix)
             .collect();
-        oxygen_check_bit += 1;
+        filter_from(report, candidates, decided, criterion)
+    }
+}
+
+/// The oxygen generator and CO2 scrubber ratings, sharing one trie.
+fn life_support(report: &Report) -> Result<(u64, u64), RatingError> {
+    let trie = PrefixTrie::new(report);
+    Ok((trie.rate(report, OXYGEN)?, trie.rate(report, CO2)?))
+}
+
+fn part2(report: &Report) -> Result<u128, RatingError> {
+    let (oxygen, co2) = life_support(report)?;
+    Ok(oxygen as u128 * co2 as u128)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct BitCount {
+    ones: usize,
+    zeros: usize,
+}
+
+/// Everything the diagnostic report says about the submarine.
+#[derive(Debug, PartialEq)]
+struct Diagnostics {
+    width: usize,
+    /// Counts for each bit position, from the leftmost digit.
+    bits: Vec<BitCount>,
+    /// Positions where ones and zeros are equally common, so gamma and
+    /// epsilon rely on the tie going to zero.
+    ties: Vec<usize>,
+    gamma: u64,
+    epsilon: u64,
+    power_consumption: u128,
+    /// The ratings are kept even if they fail, since a report that can't be
+    /// rated still has useful counts.
+    oxygen: Result<u64, RatingError>,
+    co2: Result<u64, RatingError>,
+    /// Only known when both ratings succeed.
+    life_support: Option<u128>,
+}
+
+fn diagnose(report: &Report) -> Diagnostics {
+    let ones = one_counts(report, &report.numbers);
+    let (gamma, epsilon) = power_rates(report, &ones);
+    let trie = PrefixTrie::new(report);
+    let oxygen = trie.rate(report, OXYGEN);
+    let co2 = trie.rate(report, CO2);
+    let life_support = match (&oxygen, &co2) {
+        (Ok(oxygen), Ok(co2)) => Some(*oxygen as u128 * *co2 as u128),
+        _ => None,
     };
 
-    let mut co2_candidates = numbers.clone();
-    let mut co2_check_bit: usize = 0;
-    let co2_str = loop {
-        if co2_candidates.len() == 1 {
-            break co2_candidates.get(0).unwrap();
-        }
-        let ones: usize = co2_candidates.iter().fold(0, |acc, num| {
-            if num
-                .chars()
-                .nth(co2_check_bit)
-                .expect("Oxygen check bit past length")
-                == '1'
-            {
-                acc + 1
+    let bits: Vec<BitCount> = ones
+        .iter()
+        .map(|&ones| BitCount {
+            ones,
+            zeros: report.numbers.len() - ones,
+        })
+        .collect();
+    let ties = bits
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| count.ones == count.zeros)
+        .map(|(idx, _)| idx)
+        .collect();
+
+    Diagnostics {
+        width: report.width,
+        bits,
+        ties,
+        gamma,
+        epsilon,
+        power_consumption: gamma as u128 * epsilon as u128,
+        oxygen,
+        co2,
+        life_support,
+    }
+}
+
+impl Diagnostics {
+    fn binary(&self, value: u64) -> String {
+        format!("{:0width$b}", value, width = self.width)
+    }
+
+    fn rating_text(&self, rating: &Result<u64, RatingError>) -> String {
+        match rating {
+            Ok(value) => format!("{} ({})", value, self.binary(*value)),
+            Err(error) => format!("unavailable, {}", error),
+        }
+    }
+
+    /// A rating's value and error message, each `null` if there isn't one.
+    fn rating_json(rating: &Result<u64, RatingError>) -> (String, String) {
+        match rating {
+            Ok(value) => (value.to_string(), "null".to_string()),
+            Err(error) => ("null".to_string(), format!("{:?}", error.to_string())),
+        }
+    }
+
+    fn to_table(&self) -> String {
+        let mut lines = vec!["Bit   Ones  Zeros".to_string()];
+        for (idx, count) in self.bits.iter().enumerate() {
+            let tie = if self.ties.contains(&idx) {
+                "  tie"
             } else {
-                acc
-            }
-        });
-        let threshold: f32 = co2_candidates.len() as f32 / 2.0;
-        let lcb = if (ones as f32) < threshold { '1' } else { '0' };
-        co2_candidates = co2_candidates
-            .into_iter()
-            .filter(|num| num.chars().nth(co2_check_bit).unwrap() == lcb)
+                ""
+            };
+            lines.push(format!(
+                "{:>3}  {:>5}  {:>5}{}",
+                idx, count.ones, count.zeros, tie
+            ));
+        }
+        lines.extend(vec![
+            String::new(),
+            format!("Gamma: {} ({})", self.gamma, self.binary(self.gamma)),
+            format!("Epsilon: {} ({})", self.epsilon, self.binary(self.epsilon)),
+            format!("Power consumption: {}", self.power_consumption),
+            format!("Oxygen: {}", self.rating_text(&self.oxygen)),
+            format!("CO2: {}", self.rating_text(&self.co2)),
+            format!(
+                "Life support: {}",
+                self.life_support
+                    .map_or("unavailable".to_string(), |rating| rating.to_string())
+            ),
+        ]);
+        lines.join("\n") + "\n"
+    }
+
+    fn to_json(&self) -> String {
+        let bits: Vec<String> = self
+            .bits
+            .iter()
+            .map(|count| format!(r#"{{"ones":{},"zeros":{}}}"#, count.ones, count.zeros))
             .collect();
-        co2_check_bit += 1;
+        let ties: Vec<String> = self.ties.iter().map(usize::to_string).collect();
+        let (oxygen, oxygen_error) = Self::rating_json(&self.oxygen);
+        let (co2, co2_error) = Self::rating_json(&self.co2);
+        let life_support = self
+            .life_support
+            .map_or("null".to_string(), |rating| rating.to_string());
+        format!(
+            concat!(
+                r#"{{"width":{},"bits":[{}],"ties":[{}],"gamma":{},"epsilon":{},"#,
+                r#""power_consumption":{},"oxygen":{},"oxygen_error":{},"co2":{},"#,
+                r#""co2_error":{},"life_support":{}}}"#,
+                "\n"
+            ),
+            self.width,
+            bits.join(","),
+            ties.join(","),
+            self.gamma,
+            self.epsilon,
+            self.power_consumption,
+            oxygen,
+            oxygen_error,
+            co2,
+            co2_error,
+            life_support
+        )
+    }
+}
+
+/// A report of pseudo-random numbers, from a xorshift generator so runs are
+/// repeatable.
+fn generate_report(lines: usize, width: usize, seed: u64) -> Report {
+    let mut state = seed.max(1);
+    let mut report = Report {
+        width,
+        numbers: Vec::with_capacity(lines),
     };
+    for _ in 0..lines {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        report.numbers.push(state & report.mask());
+    }
+    report
+}
 
-    let oxygen = usize::from_str_radix(oxygen_str, 2).expect("Invalid oxygen binary");
-    let co2 = usize::from_str_radix(co2_str, 2).expect("Invalid co2 binary");
+/// Time both ways of finding the life support ratings on a generated report.
+/// Random reports often run out of candidates, so the results are compared
+/// rather than unwrapped.
+fn bench(lines: usize, width: usize) -> Result<(), Box<dyn Error>> {
+    let report = generate_report(lines, width, 2021);
+    println!("{} lines of {} bits", lines, width);
 
-    println!("{}, {}", oxygen, co2);
+    let start = Instant::now();
+    let filtered = (rate(&report, OXYGEN), rate(&report, CO2));
+    println!("Filtering: {:?}", start.elapsed());
 
-    oxygen * co2
+    let start = Instant::now();
+    let trie = PrefixTrie::new(&report);
+    let walked = (trie.rate(&report, OXYGEN), trie.rate(&report, CO2));
+    println!("Trie: {:?}", start.elapsed());
+
+    assert_eq!(filtered, walked, "Rating methods disagree");
+    println!("Oxygen: {:?}", walked.0);
+    println!("CO2: {:?}", walked.1);
+    Ok(())
 }
 
-fn main() {
+fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = env::args().collect();
+    if args[1] == "bench" {
+        let lines = args.get(2).map_or(Ok(1_000_000), |n| n.parse())?;
+        let width = args.get(3).map_or(Ok(40), |n| n.parse())?;
+        if width == 0 || width > MAX_WIDTH {
+            return Err(format!("Width must be between 1 and {}", MAX_WIDTH).into());
+        }
+        return bench(lines, width);
+    }
+
     let filename = &args[1];
 
     let contents = fs::read_to_string(filename).expect("Error opening file");
-    let lines: Vec<_> = contents.lines().collect();
+    let report = Report::parse(&contents)?;
 
-    println!("Part 1: {}", part1(&lines));
-    println!("Part 2: {}", part2(&lines));
+    match args.get(2).map(String::as_str) {
+        Some("table") => print!("{}", diagnose(&report).to_table()),
+        Some("json") => print!("{}", diagnose(&report).to_json()),
+        Some(format) => return Err(format!("Unknown report format {}", format).into()),
+        None => {
+            println!("Part 1: {}", part1(&report));
+            println!("Part 2: {}", part2(&report)?);
+        }
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -119,11 +537,123 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&SAMPLE.lines().collect()), 198);
+        assert_eq!(part1(&Report::parse(SAMPLE).unwrap()), 198);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&SAMPLE.lines().collect()), 230);
+        assert_eq!(part2(&Report::parse(SAMPLE).unwrap()), Ok(230));
+    }
+
+    #[test]
+    fn parsing() {
+        let report = Report::parse(SAMPLE).unwrap();
+        assert_eq!(report.width, 5);
+        assert_eq!(&report.numbers[..3], &[0b00100, 0b11110, 0b10110]);
+        assert_eq!(one_counts(&report, &report.numbers), vec![7, 5, 8, 7, 5]);
+
+        let wide = "1".repeat(64);
+        assert_eq!(Report::parse(&wide).unwrap().numbers, vec![u64::MAX]);
+
+        let error = |text: &str| Report::parse(text).unwrap_err();
+        assert_eq!(
+            error("0101\n011"),
+            ParseError {
+                line: 2,
+                kind: ParseErrorKind::WidthMismatch(4)
+            }
+        );
+        assert_eq!(error("012").kind, ParseErrorKind::InvalidDigit('2'));
+        assert_eq!(error(&"0".repeat(65)).kind, ParseErrorKind::TooWide(65));
+        assert_eq!(error("\n").kind, ParseErrorKind::Empty);
+    }
+
+    #[test]
+    fn ratings() {
+        let report = Report::parse(SAMPLE).unwrap();
+        assert_eq!(rate(&report, OXYGEN), Ok(23));
+        assert_eq!(rate(&report, CO2), Ok(10));
+
+        let most_zeros = BitCriterion {
+            keep: Commonality::Most,
+            tie_break: false,
+        };
+        assert_eq!(rate(&report, most_zeros), Ok(0b10110));
+
+        let duplicates = Report::parse("101\n101\n001").unwrap();
+        assert_eq!(
+            rate(&duplicates, OXYGEN),
+            Err(RatingError::OutOfBits { remaining: 2 })
+        );
+        assert_eq!(rate(&duplicates, CO2), Ok(0b001));
+
+        let same_start = Report::parse("10\n11").unwrap();
+        assert_eq!(
+            rate(&same_start, CO2),
+            Err(RatingError::NoCandidates { bit: 0 })
+        );
+    }
+
+    #[test]
+    fn trie_ratings_match_filtering() {
+        let report = Report::parse(SAMPLE).unwrap();
+        assert_eq!(life_support(&report), Ok((23, 10)));
+
+        for (lines, width) in [(0, 8), (1, 8), (500, 16), (5000, 64), (3000, 6)] {
+            for seed in 1..10 {
+                let report = generate_report(lines, width, seed);
+                let trie = PrefixTrie::new(&report);
+                for criterion in [OXYGEN, CO2] {
+                    assert_eq!(trie.rate(&report, criterion), rate(&report, criterion));
+                }
+            }
+        }
+
+        let duplicates = Report::parse("101\n101\n001").unwrap();
+        assert_eq!(
+            life_support(&duplicates),
+            Err(RatingError::OutOfBits { remaining: 2 })
+        );
+    }
+
+    #[test]
+    fn diagnostics() {
+        let report = Report::parse(SAMPLE).unwrap();
+        let diagnostics = diagnose(&report);
+        assert_eq!((diagnostics.gamma, diagnostics.epsilon), (22, 9));
+        assert_eq!(diagnostics.power_consumption, 198);
+        assert_eq!(diagnostics.life_support, Some(230));
+        assert_eq!(diagnostics.bits[1], BitCount { ones: 5, zeros: 7 });
+        assert!(diagnostics.ties.is_empty());
+
+        let tied = diagnose(&Report::parse("10\n01\n11\n00").unwrap());
+        assert_eq!(tied.ties, vec![0, 1]);
+        assert_eq!((tied.gamma, tied.epsilon), (0b00, 0b11));
+        assert!(tied.to_table().contains("  0      2      2  tie"));
+        assert_eq!(
+            tied.to_json(),
+            concat!(
+                r#"{"width":2,"bits":[{"ones":2,"zeros":2},{"ones":2,"zeros":2}],"#,
+                r#""ties":[0,1],"gamma":0,"epsilon":3,"power_consumption":0,"#,
+                r#""oxygen":3,"oxygen_error":null,"co2":0,"co2_error":null,"#,
+                r#""life_support":0}"#,
+                "\n"
+            )
+        );
+
+        // The counts survive a rating that can't be found
+        let unrated = diagnose(&Report::parse("10\n11").unwrap());
+        assert_eq!(unrated.ties, vec![1]);
+        assert_eq!(unrated.oxygen, Ok(0b11));
+        assert_eq!(unrated.co2, Err(RatingError::NoCandidates { bit: 0 }));
+        assert_eq!(unrated.life_support, None);
+        let table = unrated.to_table();
+        assert!(table.contains("  1      1      1  tie"));
+        assert!(table.contains("CO2: unavailable, no candidates left after checking bit 0"));
+        assert!(unrated.to_json().ends_with(concat!(
+            r#""co2":null,"co2_error":"no candidates left after checking bit 0","#,
+            r#""life_support":null}"#,
+            "\n"
+        )));
     }
 }
@@ -1,114 +1,452 @@
-use std::{collections::HashSet, env, fs};
+use std::{collections::HashMap, env, error::Error, fmt, fs, str::FromStr};
 
-#[derive(Clone)]
-struct BingoGame {
-    boards: Vec<Vec<Vec<usize>>>,
-    numbers: Vec<usize>,
+/// A bingo card of any size, stored row by row.
+#[derive(Clone, Debug, PartialEq)]
+struct Board {
+    width: usize,
+    height: usize,
+    cells: Vec<usize>,
 }
 
-fn parse_input(input: &str) -> BingoGame {
-    let mut sections = input.split("\n\n");
-    let numbers_str = sections.next().expect("Empty input");
-    let numbers: Vec<usize> = numbers_str
-        .split(',')
-        .map(|num| num.parse().expect("Invalid number drawn"))
-        .collect();
-
-    let boards: Vec<Vec<Vec<usize>>> = sections
-        .map(|board| {
-            board
-                .lines()
-                .map(|row| {
-                    row.split(' ')
-                        .filter(|n| !n.is_empty())
-                        .map(|n| n.parse().expect("Invalid board number"))
-                        .collect()
-                })
-                .collect()
+impl Board {
+    /// Every pattern the rules allow on this board, with the indices of the
+    /// cells it covers. Rules that don't fit the board are left out.
+    fn patterns(&self, rules: &[WinRule]) -> Vec<(Pattern, Vec<usize>)> {
+        let (width, height) = (self.width, self.height);
+        let mut patterns = vec![];
+        for rule in rules.iter().filter(|rule| rule.fits(self)) {
+            match rule {
+                WinRule::Rows => patterns.extend((0..height).map(|row| {
+                    (
+                        Pattern::Row(row),
+                        (0..width).map(|col| row * width + col).collect(),
+                    )
+                })),
+                WinRule::Columns => patterns.extend((0..width).map(|col| {
+                    (
+                        Pattern::Column(col),
+                        (0..height).map(|row| row * width + col).collect(),
+                    )
+                })),
+                WinRule::Diagonals => {
+                    patterns.push((
+                        Pattern::Diagonal,
+                        (0..width).map(|i| i * width + i).collect(),
+                    ));
+                    patterns.push((
+                        Pattern::AntiDiagonal,
+                        (0..width).map(|i| i * width + width - 1 - i).collect(),
+                    ));
+                }
+                WinRule::Corners => {
+                    let mut corners = vec![0, width - 1, (height - 1) * width, height * width - 1];
+                    corners.sort_unstable();
+                    corners.dedup();
+                    patterns.push((Pattern::Corners, corners));
+                }
+                WinRule::Blackout => {
+                    patterns.push((Pattern::Blackout, (0..self.cells.len()).collect()))
+                }
+                WinRule::Custom(mask) => {
+                    patterns.push((
+                        Pattern::Custom(mask.name.clone()),
+                        (0..self.cells.len())
+                            .filter(|&idx| mask.cells[idx])
+                            .collect(),
+                    ));
+                }
+            }
+        }
+        patterns
+    }
+}
+
+/// A set of cells that wins when every one is marked, as `#` and `.` art the
+/// size of the boards it applies to.
+#[derive(Clone, Debug, PartialEq)]
+struct Mask {
+    name: String,
+    width: usize,
+    height: usize,
+    cells: Vec<bool>,
+}
+
+impl Mask {
+    fn parse(name: &str, art: &str) -> Result<Self, ParseError> {
+        let invalid = || ParseError::InvalidMask(name.to_string());
+        let rows: Vec<&str> = art
+            .lines()
+            .map(str::trim)
+            .filter(|row| !row.is_empty())
+            .collect();
+        let width = rows.first().ok_or_else(invalid)?.len();
+        let mut cells = vec![];
+        for row in rows.iter() {
+            if row.len() != width {
+                return Err(invalid());
+            }
+            for c in row.chars() {
+                match c {
+                    '#' => cells.push(true),
+                    '.' => cells.push(false),
+                    _ => return Err(invalid()),
+                }
+            }
+        }
+        if !cells.contains(&true) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            width,
+            height: rows.len(),
+            cells,
         })
-        .collect();
+    }
+}
 
-    BingoGame { numbers, boards }
+/// A kind of pattern that wins the game.
+#[derive(Clone, Debug, PartialEq)]
+enum WinRule {
+    Rows,
+    Columns,
+    /// Both diagonals, on square boards.
+    Diagonals,
+    Corners,
+    /// Every cell on the board.
+    Blackout,
+    Custom(Mask),
 }
 
-fn board_is_winner(board: &Vec<Vec<usize>>, numbers: &HashSet<usize>) -> bool {
-    board
-        .iter()
-        .any(|row| row.iter().all(|num| numbers.contains(num)))
-        || (0..=4).any(|idx: usize| board.iter().all(|row| numbers.contains(&row[idx])))
+impl WinRule {
+    /// Diagonals need a square board, and custom masks a board of the same
+    /// size.
+    fn fits(&self, board: &Board) -> bool {
+        match self {
+            WinRule::Diagonals => board.width == board.height,
+            WinRule::Custom(mask) => mask.width == board.width && mask.height == board.height,
+            _ => true,
+        }
+    }
 }
 
-fn part1(game: &BingoGame) -> usize {
-    let mut ball_dispenser = game.numbers.iter();
-    let mut drawn_numbers: HashSet<usize> = HashSet::new();
-
-    loop {
-        let ball = ball_dispenser.next().expect("Out of balls");
-        drawn_numbers.insert(*ball);
-        if drawn_numbers.len() < 5 {
-            continue;
+impl fmt::Display for WinRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WinRule::Rows => write!(f, "rows"),
+            WinRule::Columns => write!(f, "columns"),
+            WinRule::Diagonals => write!(f, "diagonals"),
+            WinRule::Corners => write!(f, "corners"),
+            WinRule::Blackout => write!(f, "blackout"),
+            WinRule::Custom(mask) => write!(f, "mask {}", mask.name),
+        }
+    }
+}
+
+impl FromStr for WinRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rows" => Ok(WinRule::Rows),
+            "columns" => Ok(WinRule::Columns),
+            "diagonals" => Ok(WinRule::Diagonals),
+            "corners" => Ok(WinRule::Corners),
+            "blackout" => Ok(WinRule::Blackout),
+            _ => Err(format!("Unknown win rule {}", s)),
         }
+    }
+}
 
-        if let Some(board) = game
-            .boards
+/// The pattern a board completed.
+#[derive(Clone, Debug, PartialEq)]
+enum Pattern {
+    Row(usize),
+    Column(usize),
+    /// Top left to bottom right.
+    Diagonal,
+    /// Top right to bottom left.
+    AntiDiagonal,
+    Corners,
+    Blackout,
+    Custom(String),
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pattern::Row(row) => write!(f, "row {}", row),
+            Pattern::Column(col) => write!(f, "column {}", col),
+            Pattern::Diagonal => write!(f, "diagonal"),
+            Pattern::AntiDiagonal => write!(f, "anti-diagonal"),
+            Pattern::Corners => write!(f, "corners"),
+            Pattern::Blackout => write!(f, "blackout"),
+            Pattern::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Win {
+    board: usize,
+    /// The index of the winning ball in the draw order.
+    draw: usize,
+    number: usize,
+    pattern: Pattern,
+    score: usize,
+}
+
+struct BingoGame {
+    boards: Vec<Board>,
+    numbers: Vec<usize>,
+    rules: Vec<WinRule>,
+}
+
+impl BingoGame {
+    /// Switch to a new set of rules, rejecting any rule that fits none of the
+    /// boards, since it could never be completed.
+    fn set_rules(&mut self, rules: Vec<WinRule>) -> Result<(), ParseError> {
+        if let Some(rule) = rules
             .iter()
-            .find(|board| board_is_winner(board, &drawn_numbers))
+            .find(|rule| !self.boards.iter().any(|board| rule.fits(board)))
         {
-            break ball
-                * board
-                    .iter()
-                    .flat_map(|row| row.iter())
-                    .filter(|&num| !drawn_numbers.contains(num))
-                    .copied()
-                    .reduce(|acc, num| acc + num)
-                    .unwrap();
+            return Err(ParseError::UnusableRule(rule.to_string()));
         }
+        self.rules = rules;
+        Ok(())
     }
-}
 
-fn part2(init_game: &BingoGame) -> usize {
-    let mut ball_dispenser = init_game.numbers.iter();
-    let mut drawn_numbers: HashSet<usize> = HashSet::new();
-    let mut game = init_game.clone();
+    /// Each board's first win, in the order they happen. Boards winning on the
+    /// same ball are listed by board index, and boards that never win are left
+    /// out.
+    ///
+    /// Every number is indexed to the cells it appears in, and each pattern
+    /// keeps a count of its marked cells, so a draw only touches the cells
+    /// holding that number.
+    fn wins(&self) -> Vec<Win> {
+        let patterns: Vec<_> = self
+            .boards
+            .iter()
+            .map(|board| board.patterns(&self.rules))
+            .collect();
 
-    loop {
-        let ball = ball_dispenser.next().expect("Out of balls");
-        drawn_numbers.insert(*ball);
-        if drawn_numbers.len() < 5 {
-            continue;
+        // The patterns covering each cell of each board
+        let mut cell_patterns: Vec<Vec<Vec<usize>>> = self
+            .boards
+            .iter()
+            .map(|board| vec![vec![]; board.cells.len()])
+            .collect();
+        for (board_idx, board_patterns) in patterns.iter().enumerate() {
+            for (pattern_idx, (_, cells)) in board_patterns.iter().enumerate() {
+                for &cell in cells.iter() {
+                    cell_patterns[board_idx][cell].push(pattern_idx);
+                }
+            }
         }
 
-        let pre_check_boards = game.boards.clone();
+        let mut positions: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+        for (board_idx, board) in self.boards.iter().enumerate() {
+            for (cell, &number) in board.cells.iter().enumerate() {
+                positions.entry(number).or_default().push((board_idx, cell));
+            }
+        }
 
-        game.boards = game
+        let mut hits: Vec<Vec<usize>> = patterns
+            .iter()
+            .map(|board_patterns| vec![0; board_patterns.len()])
+            .collect();
+        let mut marked: Vec<Vec<bool>> = self
+            .boards
+            .iter()
+            .map(|board| vec![false; board.cells.len()])
+            .collect();
+        let mut unmarked_sums: Vec<usize> = self
             .boards
-            .into_iter()
-            .filter(|board| !board_is_winner(board, &drawn_numbers))
+            .iter()
+            .map(|board| board.cells.iter().sum())
             .collect();
+        let mut won = vec![false; self.boards.len()];
+        let mut wins = vec![];
+
+        for (draw, &number) in self.numbers.iter().enumerate() {
+            if wins.len() == self.boards.len() {
+                break;
+            }
 
-        if game.boards.len() == 0 {
-            break ball
-                * pre_check_boards[0]
-                    .iter()
-                    .flat_map(|row| row.iter())
-                    .filter(|&num| !drawn_numbers.contains(num))
-                    .copied()
-                    .reduce(|acc, num| acc + num)
-                    .unwrap();
+            // (board, pattern) pairs completed by this ball
+            let mut completed = vec![];
+            for &(board_idx, cell) in positions.get(&number).into_iter().flatten() {
+                if won[board_idx] || marked[board_idx][cell] {
+                    continue;
+                }
+                marked[board_idx][cell] = true;
+                unmarked_sums[board_idx] -= number;
+                for &pattern_idx in cell_patterns[board_idx][cell].iter() {
+                    hits[board_idx][pattern_idx] += 1;
+                    if hits[board_idx][pattern_idx] == patterns[board_idx][pattern_idx].1.len() {
+                        completed.push((board_idx, pattern_idx));
+                    }
+                }
+            }
+
+            // A ball can complete several patterns at once, so report the
+            // first one the rules list
+            completed.sort_unstable();
+            completed.dedup_by_key(|(board_idx, _)| *board_idx);
+            for (board_idx, pattern_idx) in completed {
+                won[board_idx] = true;
+                wins.push(Win {
+                    board: board_idx,
+                    draw,
+                    number,
+                    pattern: patterns[board_idx][pattern_idx].0.clone(),
+                    score: number * unmarked_sums[board_idx],
+                });
+            }
+        }
+
+        wins
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ParseError {
+    InvalidNumber(String),
+    /// A board with no numbers, counting boards from 0.
+    EmptyBoard(usize),
+    /// A board row with a different length to the board's first row.
+    RaggedBoard {
+        board: usize,
+        row: usize,
+    },
+    InvalidMask(String),
+    /// A win rule that doesn't fit any of the boards.
+    UnusableRule(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidNumber(num) => write!(f, "Invalid number {}", num),
+            ParseError::EmptyBoard(board) => write!(f, "Board {} is empty", board),
+            ParseError::RaggedBoard { board, row } => {
+                write!(f, "Row {} of board {} is the wrong length", row, board)
+            }
+            ParseError::UnusableRule(rule) => write!(f, "Win rule {} doesn't fit any board", rule),
+            ParseError::InvalidMask(name) => {
+                write!(f, "Mask {} isn't rectangular # and . art", name)
+            }
         }
     }
 }
 
-fn main() {
+impl Error for ParseError {}
+
+fn parse_number(num: &str) -> Result<usize, ParseError> {
+    num.parse()
+        .map_err(|_| ParseError::InvalidNumber(num.to_string()))
+}
+
+fn parse_board(idx: usize, text: &str) -> Result<Board, ParseError> {
+    let mut cells = vec![];
+    let mut width = 0;
+    let mut height = 0;
+    for (row_idx, row) in text.lines().enumerate() {
+        let before = cells.len();
+        for num in row.split_whitespace() {
+            cells.push(parse_number(num)?);
+        }
+        let row_width = cells.len() - before;
+        if row_idx == 0 {
+            width = row_width;
+        } else if row_width != width {
+            return Err(ParseError::RaggedBoard {
+                board: idx,
+                row: row_idx,
+            });
+        }
+        height += 1;
+    }
+
+    if width == 0 {
+        return Err(ParseError::EmptyBoard(idx));
+    }
+    Ok(Board {
+        width,
+        height,
+        cells,
+    })
+}
+
+fn parse_input(input: &str) -> Result<BingoGame, ParseError> {
+    let mut sections = input.split("\n\n");
+    let numbers_str = sections.next().expect("Empty input");
+    let numbers = numbers_str
+        .trim()
+        .split(',')
+        .map(parse_number)
+        .collect::<Result<_, _>>()?;
+
+    let boards = sections
+        .map(str::trim)
+        .filter(|board| !board.is_empty())
+        .enumerate()
+        .map(|(idx, board)| parse_board(idx, board))
+        .collect::<Result<_, _>>()?;
+
+    Ok(BingoGame {
+        numbers,
+        boards,
+        rules: vec![WinRule::Rows, WinRule::Columns],
+    })
+}
+
+fn part1(game: &BingoGame) -> usize {
+    game.wins().first().expect("No board won").score
+}
+
+fn part2(game: &BingoGame) -> usize {
+    game.wins().last().expect("No board won").score
+}
+
+/// Read win rules from a comma-separated list such as `rows,diagonals`, where
+/// `mask:FILE` loads a custom mask from a file.
+fn parse_rules(list: &str) -> Result<Vec<WinRule>, Box<dyn Error>> {
+    list.split(',')
+        .map(|rule| match rule.strip_prefix("mask:") {
+            Some(path) => Ok(WinRule::Custom(Mask::parse(
+                path,
+                &fs::read_to_string(path)?,
+            )?)),
+            None => Ok(rule.parse()?),
+        })
+        .collect()
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = env::args().collect();
     let filename = &args[1];
 
     let contents = fs::read_to_string(filename).expect("Error opening file");
 
-    let game = parse_input(&contents);
+    let mut game = parse_input(&contents)?;
+
+    if let Some(rules) = args.get(2) {
+        game.set_rules(parse_rules(rules)?)?;
+        for win in game.wins() {
+            println!(
+                "Board {} won with {} on draw {} ({}), scoring {}",
+                win.board, win.pattern, win.draw, win.number, win.score
+            );
+        }
+        return Ok(());
+    }
 
     println!("Part 1: {}", part1(&game));
     println!("Part 2: {}", part2(&game));
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -119,11 +457,131 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse_input(SAMPLE)), 4512);
+        assert_eq!(part1(&parse_input(SAMPLE).unwrap()), 4512);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse_input(SAMPLE)), 1924);
+        assert_eq!(part2(&parse_input(SAMPLE).unwrap()), 1924);
+    }
+
+    #[test]
+    fn other_sizes() {
+        let game = parse_input("4,1,7,9,11\n\n1 2 3\n4 5 6\n\n7 8\n9 10\n11 12\n").unwrap();
+        assert_eq!(
+            game.boards[1],
+            Board {
+                width: 2,
+                height: 3,
+                cells: vec![7, 8, 9, 10, 11, 12]
+            }
+        );
+        // Columns are two numbers long on the wide board, so it wins on
+        // drawing 1, and three long on the tall board
+        assert_eq!(part1(&game), 2 + 3 + 5 + 6);
+        assert_eq!(part2(&game), 11 * (8 + 10 + 12));
+
+        assert_eq!(
+            parse_input("1\n\n1 2\n3\n").err(),
+            Some(ParseError::RaggedBoard { board: 0, row: 1 })
+        );
+        assert_eq!(
+            parse_input("1,x\n\n1").err(),
+            Some(ParseError::InvalidNumber("x".to_string()))
+        );
+    }
+
+    #[test]
+    fn win_rules() {
+        let mut game = parse_input(SAMPLE).unwrap();
+        let wins = game.wins();
+        let summary: Vec<_> = wins
+            .iter()
+            .map(|win| (win.board, win.draw, win.number, win.score))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(2, 11, 24, 4512), (0, 13, 16, 2192), (1, 14, 13, 1924)]
+        );
+        assert_eq!(
+            wins[0],
+            Win {
+                board: 2,
+                draw: 11,
+                number: 24,
+                pattern: Pattern::Row(0),
+                score: 4512
+            }
+        );
+
+        game.set_rules(vec![WinRule::Diagonals, WinRule::Corners])
+            .unwrap();
+        game.numbers = vec![22, 0, 1, 7, 19, 4, 9, 23, 11, 2];
+        let wins = game.wins();
+        assert_eq!(
+            (wins[0].board, wins[0].draw, &wins[0].pattern),
+            (0, 4, &Pattern::Corners)
+        );
+        assert_eq!(
+            (wins[1].board, wins[1].draw, &wins[1].pattern),
+            (2, 9, &Pattern::AntiDiagonal)
+        );
+
+        let plus = Mask::parse("plus", "..#..\n..#..\n#####\n..#..\n..#..").unwrap();
+        game.set_rules(vec![WinRule::Custom(plus), WinRule::Blackout])
+            .unwrap();
+        game.numbers = (0..100).collect();
+        let wins = game.wins();
+        assert_eq!(wins.len(), 3);
+        assert!(wins
+            .iter()
+            .all(|win| win.pattern == Pattern::Custom("plus".to_string())));
+
+        let small = Mask::parse("small", "#.\n.#").unwrap();
+        assert_eq!(
+            game.set_rules(vec![WinRule::Rows, WinRule::Custom(small)]),
+            Err(ParseError::UnusableRule("mask small".to_string()))
+        );
+        let mut tall = parse_input("1\n\n1 2\n3 4\n5 6").unwrap();
+        assert_eq!(
+            tall.set_rules(vec![WinRule::Diagonals]),
+            Err(ParseError::UnusableRule("diagonals".to_string()))
+        );
+
+        assert_eq!(
+            Mask::parse("bad", "#.\n#"),
+            Err(ParseError::InvalidMask("bad".to_string()))
+        );
+        assert_eq!(
+            Mask::parse("empty", "..").unwrap_err(),
+            ParseError::InvalidMask("empty".to_string())
+        );
+    }
+
+    #[test]
+    fn repeated_numbers() {
+        // Drawing 1 twice mustn't count twice towards the top row of the first
+        // board, but a number appearing twice on a board marks both cells
+        let game = parse_input("1,1,2,2,3\n\n1 2 3\n4 5 6\n\n1 1\n2 9").unwrap();
+        let wins = game.wins();
+        assert_eq!(
+            wins,
+            vec![
+                Win {
+                    board: 1,
+                    draw: 0,
+                    number: 1,
+                    pattern: Pattern::Row(0),
+                    score: 2 + 9
+                },
+                Win {
+                    board: 0,
+                    draw: 4,
+                    number: 3,
+                    pattern: Pattern::Row(0),
+                    score: 3 * (4 + 5 + 6)
+                }
+            ]
+        );
     }
 }
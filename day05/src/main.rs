@@ -1,86 +1,40 @@
+use point_2d::{Point2D, Segment};
 use std::{collections::HashMap, env, fs};
 
-type Line = ((isize, isize), (isize, isize));
+type Line = Segment<isize>;
 
 fn parse_input(input: &str) -> Vec<Line> {
     input
         .lines()
         .map(|line| {
-            line.split(" -> ")
-                .map(|point| {
-                    point
-                        .split(',')
-                        .map(|n| n.parse().expect("Invalid number"))
-                        .collect::<Vec<isize>>()
-                })
-                .collect::<Vec<Vec<isize>>>()
+            let (start, end) = line.split_once(" -> ").expect("Missing arrow");
+            Segment::new(
+                start.parse().expect("Invalid point"),
+                end.parse().expect("Invalid point"),
+            )
         })
-        .map(|pts| ((pts[0][0], pts[0][1]), (pts[1][0], pts[1][1])))
         .collect()
 }
 
-fn part1(lines: &[Line]) -> usize {
-    let horizontals: Vec<&Line> = lines.iter().filter(|((_, y1), (_, y2))| y1 == y2).collect();
-
-    let verticals: Vec<&Line> = lines.iter().filter(|((x1, _), (x2, _))| x1 == x2).collect();
-
-    let mut point_counts: HashMap<(isize, isize), usize> = HashMap::new();
-
-    for ((x, y1), (_, y2)) in verticals {
-        let range = if y1 < y2 { *y1..=*y2 } else { *y2..=*y1 };
-        for y in range {
-            point_counts
-                .entry((*x, y))
-                .and_modify(|c| *c += 1)
-                .or_insert(1);
-        }
-    }
+fn count_overlaps<'a>(lines: impl Iterator<Item = &'a Line>) -> usize {
+    let mut point_counts: HashMap<Point2D<isize>, usize> = HashMap::new();
 
-    for ((x1, y), (x2, _)) in horizontals {
-        let range = if x1 < x2 { *x1..=*x2 } else { *x2..=*x1 };
-        for x in range {
-            point_counts
-                .entry((x, *y))
-                .and_modify(|c| *c += 1)
-                .or_insert(1);
-        }
+    for point in lines.flat_map(|line| line.points()) {
+        point_counts
+            .entry(point)
+            .and_modify(|c| *c += 1)
+            .or_insert(1);
     }
 
     point_counts.values().filter(|&&c| c >= 2).count()
 }
 
-fn part2(lines: &[Line]) -> usize {
-    let verticals: Vec<&Line> = lines.iter().filter(|((x1, _), (x2, _))| x1 == x2).collect();
-    let rest: Vec<&Line> = lines.iter().filter(|((x1, _), (x2, _))| x1 != x2).collect();
-
-    let mut point_counts: HashMap<(isize, isize), usize> = HashMap::new();
-
-    for ((x, y1), (_, y2)) in verticals {
-        let range = if y1 < y2 { *y1..=*y2 } else { *y2..=*y1 };
-        for y in range {
-            point_counts
-                .entry((*x, y))
-                .and_modify(|c| *c += 1)
-                .or_insert(1);
-        }
-    }
-
-    for ((x1, y1), (x2, y2)) in rest {
-        // According to problem constraint, slope will always be integral
-        let slope = (y2 - y1) / (x2 - x1);
-        let range = if x1 < x2 { *x1..=*x2 } else { *x2..=*x1 };
-        let mut y = if x1 < x2 { *y1 } else { *y2 };
-        for x in range {
-            point_counts
-                .entry((x, y))
-                .and_modify(|c| *c += 1)
-                .or_insert(1);
-
-            y += slope;
-        }
-    }
+fn part1(lines: &[Line]) -> usize {
+    count_overlaps(lines.iter().filter(|line| line.is_axis_aligned()))
+}
 
-    point_counts.values().filter(|&&c| c >= 2).count()
+fn part2(lines: &[Line]) -> usize {
+    count_overlaps(lines.iter())
 }
 
 fn main() {
@@ -33,10 +33,7 @@ fn deduce(display: &[&str]) -> usize {
     let all: HashSet<char> = ['a', 'b', 'c', 'd', 'e', 'f', 'g'].into();
     let mut possibilities: Vec<HashSet<char>> = (0..7).map(|_| all.clone()).collect();
 
-    let digits: Vec<HashSet<char>> = (&display[0..10])
-        .iter()
-        .map(|d| d.chars().collect())
-        .collect();
+    let digits: Vec<HashSet<char>> = display[0..10].iter().map(|d| d.chars().collect()).collect();
 
     let one = digits.iter().find(|d| d.len() == 2).expect("No 1 found");
 
@@ -135,7 +132,7 @@ fn deduce(display: &[&str]) -> usize {
         .map(|(a, b)| (b, a))
         .collect();
 
-    (&display[10..14])
+    display[10..14]
         .iter()
         .map(|d| {
             digit_lookup
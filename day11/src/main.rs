@@ -16,7 +16,7 @@ fn parse_input(input: &str) -> Grid {
         .flat_map(|(y, line)| {
             line.chars().enumerate().map(move |(x, octopus)| {
                 (
-                    Point2D(x as isize, y as isize),
+                    Point2D::try_from(Point2D(x, y)).expect("Grid too large"),
                     Octopus {
                         flashed: false,
                         level: octopus.to_digit(10).expect("Non-digit found"),
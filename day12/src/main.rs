@@ -15,7 +15,7 @@ use Cave::*;
 
 type Network<'a> = HashMap<Cave<'a>, Vec<Cave<'a>>>;
 
-fn parse_input(input: &str) -> Network {
+fn parse_input(input: &str) -> Network<'_> {
     let mut network: Network = HashMap::new();
     for line in input.lines() {
         let cave_pair: Vec<_> = line
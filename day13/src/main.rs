@@ -1,9 +1,5 @@
-use std::{
-    collections::HashSet,
-    env,
-    fmt::{Error, Write},
-    fs,
-};
+use point_2d::{Point2D, PointSet, Rect, YAxis};
+use std::{env, fs};
 
 #[derive(Clone, Copy)]
 enum Fold {
@@ -11,12 +7,11 @@ enum Fold {
     Y(u16),
 }
 
-#[derive(Clone, Copy, Eq, Hash, PartialEq)]
-struct Dot(u16, u16);
+type Dot = Point2D<u16>;
 
 #[derive(Clone)]
 struct Paper {
-    dots: HashSet<Dot>,
+    dots: PointSet<u16>,
     fold_stack: Vec<Fold>,
 }
 
@@ -27,25 +22,25 @@ impl Paper {
             let moved_dots: Vec<_> = match fold_line {
                 Fold::X(fx) => self
                     .dots
-                    .iter()
-                    .filter(|Dot(x, _)| x > &fx)
+                    .points()
+                    .filter(|Point2D(x, _)| x > &fx)
                     .copied()
                     .collect(),
                 Fold::Y(fy) => self
                     .dots
-                    .iter()
-                    .filter(|Dot(_, y)| y > &fy)
+                    .points()
+                    .filter(|Point2D(_, y)| y > &fy)
                     .copied()
                     .collect(),
             };
 
             for dot in moved_dots {
                 let new_dot = match (fold_line, dot) {
-                    (Fold::X(fx), Dot(x, y)) => Dot(x - 2 * (x - fx), y),
-                    (Fold::Y(fy), Dot(x, y)) => Dot(x, y - 2 * (y - fy)),
+                    (Fold::X(fx), Point2D(x, y)) => Point2D(x - 2 * (x - fx), y),
+                    (Fold::Y(fy), Point2D(x, y)) => Point2D(x, y - 2 * (y - fy)),
                 };
                 self.dots.remove(&dot);
-                self.dots.insert(new_dot);
+                self.dots.mark(new_dot);
             }
 
             true
@@ -58,15 +53,9 @@ impl Paper {
 fn parse_input(input: &str) -> Paper {
     let parts: Vec<_> = input.split("\n\n").collect();
 
-    let dots: HashSet<Dot> = parts[0]
+    let dots: PointSet<u16> = parts[0]
         .lines()
-        .map(|line| {
-            let mut points = line.split(',');
-            Dot(
-                points.next().unwrap().parse().unwrap(),
-                points.next().unwrap().parse().unwrap(),
-            )
-        })
+        .map(|line| line.parse::<Dot>().unwrap())
         .collect();
 
     let fold_stack: Vec<_> = parts[1]
@@ -93,33 +82,32 @@ fn part1(init: &Paper) -> usize {
     paper.dots.len()
 }
 
-fn part2(mut paper: Paper) -> Result<String, Error> {
+fn part2(mut paper: Paper) -> String {
     loop {
         if !paper.fold() {
             break;
         }
     }
 
-    let max_x = paper.dots.iter().map(|Dot(x, _)| x).max().unwrap();
-    let max_y = paper.dots.iter().map(|Dot(_, y)| y).max().unwrap();
-
-    let mut output: String = String::new();
-
-    for y in 0..=*max_y {
-        for x in 0..=*max_x {
-            if paper.dots.contains(&Dot(x, y)) {
-                output.write_char('#')?;
+    // Render from the origin rather than the bounding box so the letters
+    // keep their left and top margins
+    let bounds = paper.dots.bounds().expect("No dots left on paper");
+    let sheet = Rect::new(Point2D(0, 0), bounds.max);
+
+    paper.dots.render_region(
+        &sheet,
+        YAxis::Down,
+        |dot| {
+            if dot.is_some() {
+                '#'
             } else {
-                output.write_char('.')?;
+                '.'
             }
-        }
-        output.write_char('\n')?;
-    }
-
-    Ok(output)
+        },
+    )
 }
 
-fn main() -> Result<(), Error> {
+fn main() {
     let args: Vec<String> = env::args().collect();
     let filename = &args[1];
 
@@ -129,9 +117,7 @@ fn main() -> Result<(), Error> {
 
     println!("Part 1: {}", part1(&paper));
     println!("Part 2:");
-    print!("{}", part2(paper)?);
-
-    Ok(())
+    print!("{}", part2(paper));
 }
 
 #[cfg(test)]
@@ -148,9 +134,6 @@ mod test {
 
     #[test]
     fn part2_example() {
-        assert_eq!(
-            part2(parse_input(SAMPLE)).unwrap(),
-            PART2_OUTPUT.to_string()
-        );
+        assert_eq!(part2(parse_input(SAMPLE)), PART2_OUTPUT.to_string());
     }
 }
@@ -162,9 +162,7 @@ fn hex_to_bin(hex: &str) -> String {
 
 fn part1(packet: &Packet) -> usize {
     match packet {
-        Packet::Operation(op) => {
-            op.version as usize + op.children.iter().map(|p| part1(p)).sum::<usize>()
-        }
+        Packet::Operation(op) => op.version as usize + op.children.iter().map(part1).sum::<usize>(),
         Packet::Literal(lp) => lp.version as usize,
     }
 }
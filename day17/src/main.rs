@@ -1,9 +1,12 @@
+use point_2d::{Point2D, Rect};
 use std::{
     collections::{HashMap, HashSet},
     env,
 };
 
-fn part1(min_y: i32, max_y: i32) -> i32 {
+fn part1(target: &Rect<i32>) -> i32 {
+    let min_y = target.min.1;
+
     let mut max = 0;
     let mut check = 1;
     loop {
@@ -11,7 +14,7 @@ fn part1(min_y: i32, max_y: i32) -> i32 {
         let mut pos = 0;
         loop {
             pos += next;
-            if pos >= min_y && pos <= max_y {
+            if target.y_interval().contains(pos) {
                 max = check;
                 break;
             }
@@ -29,7 +32,13 @@ fn part1(min_y: i32, max_y: i32) -> i32 {
     max * (max + 1) / 2
 }
 
-fn part2(min_x: i32, max_x: i32, min_y: i32, max_y: i32) -> usize {
+fn part2(target: &Rect<i32>) -> usize {
+    let Rect {
+        min: Point2D(min_x, min_y),
+        max: Point2D(max_x, _),
+    } = *target;
+    let (x_range, y_range) = (target.x_interval(), target.y_interval());
+
     // Find y trajectories and valid steps for them
     let mut step_to_ys: HashMap<i32, Vec<i32>> = HashMap::new();
     let mut max_step = 0;
@@ -40,7 +49,7 @@ fn part2(min_x: i32, max_x: i32, min_y: i32, max_y: i32) -> usize {
         loop {
             pos += next;
             step += 1;
-            if pos >= min_y && pos <= max_y {
+            if y_range.contains(pos) {
                 step_to_ys
                     .entry(step)
                     .and_modify(|v| v.push(y))
@@ -92,7 +101,7 @@ fn part2(min_x: i32, max_x: i32, min_y: i32, max_y: i32) -> usize {
                 break;
             }
 
-            if pos >= min_x && pos <= max_x {
+            if x_range.contains(pos) {
                 if let Some(ys) = step_to_ys.get(&step) {
                     for y in ys.iter() {
                         trajectories.insert((x, *y));
@@ -115,6 +124,8 @@ fn main() {
     let min_y: i32 = args.next().unwrap().parse().unwrap();
     let max_y: i32 = args.next().unwrap().parse().unwrap();
 
-    println!("Part 1: {}", part1(min_y, max_y));
-    println!("Part 2: {}", part2(min_x, max_x, min_y, max_y));
+    let target = Rect::new(Point2D(min_x, min_y), Point2D(max_x, max_y));
+
+    println!("Part 1: {}", part1(&target));
+    println!("Part 2: {}", part2(&target));
 }
@@ -0,0 +1,157 @@
+use crate::Point2D;
+use num::PrimInt;
+use std::{convert::TryFrom, error::Error, fmt};
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum PointConversionError {
+    /// A coordinate or index doesn't fit in the target type.
+    OutOfRange,
+    /// The point is negative or past the right-hand edge of the grid.
+    OutsideGrid,
+    /// A flat grid can't have a width of zero.
+    ZeroWidth,
+}
+
+impl fmt::Display for PointConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PointConversionError::OutOfRange => {
+                write!(f, "coordinate out of range for target type")
+            }
+            PointConversionError::OutsideGrid => write!(f, "point lies outside the grid"),
+            PointConversionError::ZeroWidth => write!(f, "grid width must be non-zero"),
+        }
+    }
+}
+
+impl Error for PointConversionError {}
+
+macro_rules! impl_try_from_point {
+    ($from:ty => $($to:ty),*) => {
+        $(
+            impl TryFrom<Point2D<$from>> for Point2D<$to> {
+                type Error = PointConversionError;
+
+                fn try_from(point: Point2D<$from>) -> Result<Self, Self::Error> {
+                    let convert =
+                        |n| <$to>::try_from(n).map_err(|_| PointConversionError::OutOfRange);
+                    Ok(Point2D(convert(point.0)?, convert(point.1)?))
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_point!(i8 => i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_try_from_point!(i16 => i8, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_try_from_point!(i32 => i8, i16, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_try_from_point!(i64 => i8, i16, i32, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_try_from_point!(i128 => i8, i16, i32, i64, isize, u8, u16, u32, u64, u128, usize);
+impl_try_from_point!(isize => i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, usize);
+impl_try_from_point!(u8 => i8, i16, i32, i64, i128, isize, u16, u32, u64, u128, usize);
+impl_try_from_point!(u16 => i8, i16, i32, i64, i128, isize, u8, u32, u64, u128, usize);
+impl_try_from_point!(u32 => i8, i16, i32, i64, i128, isize, u8, u16, u64, u128, usize);
+impl_try_from_point!(u64 => i8, i16, i32, i64, i128, isize, u8, u16, u32, u128, usize);
+impl_try_from_point!(u128 => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+impl_try_from_point!(usize => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128);
+
+impl<T> From<(T, T)> for Point2D<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2D(x, y)
+    }
+}
+
+impl<T> From<Point2D<T>> for (T, T) {
+    fn from(point: Point2D<T>) -> Self {
+        (point.0, point.1)
+    }
+}
+
+impl<T> From<[T; 2]> for Point2D<T> {
+    fn from([x, y]: [T; 2]) -> Self {
+        Point2D(x, y)
+    }
+}
+
+impl<T> From<Point2D<T>> for [T; 2] {
+    fn from(point: Point2D<T>) -> Self {
+        [point.0, point.1]
+    }
+}
+
+impl<T: PrimInt> Point2D<T> {
+    /// The position of the point in a flat, row-major `Vec` holding a grid of
+    /// the given width.
+    pub fn to_index(&self, width: usize) -> Result<usize, PointConversionError> {
+        if width == 0 {
+            return Err(PointConversionError::ZeroWidth);
+        }
+        let x = self.0.to_usize().ok_or(PointConversionError::OutsideGrid)?;
+        let y = self.1.to_usize().ok_or(PointConversionError::OutsideGrid)?;
+        if x >= width {
+            return Err(PointConversionError::OutsideGrid);
+        }
+        y.checked_mul(width)
+            .and_then(|row| row.checked_add(x))
+            .ok_or(PointConversionError::OutOfRange)
+    }
+
+    /// The point at a position in a flat, row-major `Vec` holding a grid of
+    /// the given width.
+    pub fn from_index(index: usize, width: usize) -> Result<Self, PointConversionError> {
+        if width == 0 {
+            return Err(PointConversionError::ZeroWidth);
+        }
+        let x = T::from(index % width).ok_or(PointConversionError::OutOfRange)?;
+        let y = T::from(index / width).ok_or(PointConversionError::OutOfRange)?;
+        Ok(Point2D(x, y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn between_types() {
+        assert_eq!(
+            Point2D::<isize>::try_from(Point2D(3usize, 4)),
+            Ok(Point2D(3, 4))
+        );
+        assert_eq!(
+            Point2D::<usize>::try_from(Point2D(3isize, -4)),
+            Err(PointConversionError::OutOfRange)
+        );
+        assert_eq!(
+            Point2D::<u8>::try_from(Point2D(256u16, 0)),
+            Err(PointConversionError::OutOfRange)
+        );
+
+        let tuple: (i32, i32) = Point2D(1, 2).into();
+        let array: [i32; 2] = Point2D::from(tuple).into();
+        assert_eq!(Point2D::from(array), Point2D(1, 2));
+    }
+
+    #[test]
+    fn flat_indices() {
+        let width = 10;
+        assert_eq!(Point2D(3, 2).to_index(width), Ok(23));
+        assert_eq!(Point2D::<i32>::from_index(23, width), Ok(Point2D(3, 2)));
+        assert_eq!(
+            Point2D(-1, 2).to_index(width),
+            Err(PointConversionError::OutsideGrid)
+        );
+        assert_eq!(
+            Point2D(10, 2).to_index(width),
+            Err(PointConversionError::OutsideGrid)
+        );
+        assert_eq!(
+            Point2D(1, 1).to_index(0),
+            Err(PointConversionError::ZeroWidth)
+        );
+        assert_eq!(
+            Point2D::<i8>::from_index(10_000, width),
+            Err(PointConversionError::OutOfRange)
+        );
+    }
+}
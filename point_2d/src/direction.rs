@@ -0,0 +1,172 @@
+use crate::Point2D;
+use num::{PrimInt, Signed};
+use std::{convert::TryFrom, error::Error, fmt, str::FromStr};
+
+/// A compass heading on a discrete grid.
+///
+/// Offsets follow the usual puzzle-input convention of y growing downwards,
+/// so `North` is `Point2D(0, -1)` and `South` is `Point2D(0, 1)`.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+use Direction::*;
+
+impl Direction {
+    /// The four cardinal directions, clockwise from north.
+    pub const CARDINAL: [Direction; 4] = [North, East, South, West];
+
+    /// All eight directions, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        North, NorthEast, East, SouthEast, South, SouthWest, West, NorthWest,
+    ];
+
+    fn eighths(self) -> usize {
+        self as usize
+    }
+
+    fn from_eighths(eighths: usize) -> Self {
+        Self::ALL[eighths % 8]
+    }
+
+    pub fn is_cardinal(self) -> bool {
+        self.eighths().is_multiple_of(2)
+    }
+
+    /// Rotate 90° counterclockwise.
+    pub fn turn_left(self) -> Self {
+        Self::from_eighths(self.eighths() + 6)
+    }
+
+    /// Rotate 90° clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::from_eighths(self.eighths() + 2)
+    }
+
+    /// Rotate 45° counterclockwise.
+    pub fn turn_left_45(self) -> Self {
+        Self::from_eighths(self.eighths() + 7)
+    }
+
+    /// Rotate 45° clockwise.
+    pub fn turn_right_45(self) -> Self {
+        Self::from_eighths(self.eighths() + 1)
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::from_eighths(self.eighths() + 4)
+    }
+
+    /// The unit offset of a single step in this direction.
+    pub fn offset<T: PrimInt + Signed>(self) -> Point2D<T> {
+        let one = T::one();
+        let zero = T::zero();
+        match self {
+            North => Point2D(zero, -one),
+            NorthEast => Point2D(one, -one),
+            East => Point2D(one, zero),
+            SouthEast => Point2D(one, one),
+            South => Point2D(zero, one),
+            SouthWest => Point2D(-one, one),
+            West => Point2D(-one, zero),
+            NorthWest => Point2D(-one, -one),
+        }
+    }
+}
+
+impl<T: PrimInt + Signed> From<Direction> for Point2D<T> {
+    fn from(dir: Direction) -> Self {
+        dir.offset()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseDirectionError(String);
+
+impl fmt::Display for ParseDirectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid direction {:?}", self.0)
+    }
+}
+
+impl Error for ParseDirectionError {}
+
+impl FromStr for Direction {
+    type Err = ParseDirectionError;
+
+    /// Accepts `U`/`D`/`L`/`R`, compass letters (`N`, `NE`, `E`, ...) or the
+    /// full compass names, case-insensitively.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "u" | "n" | "up" | "north" => Ok(North),
+            "ne" | "northeast" => Ok(NorthEast),
+            "r" | "e" | "right" | "east" => Ok(East),
+            "se" | "southeast" => Ok(SouthEast),
+            "d" | "s" | "down" | "south" => Ok(South),
+            "sw" | "southwest" => Ok(SouthWest),
+            "l" | "w" | "left" | "west" => Ok(West),
+            "nw" | "northwest" => Ok(NorthWest),
+            _ => Err(ParseDirectionError(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = ParseDirectionError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        c.encode_utf8(&mut [0; 4]).parse()
+    }
+}
+
+impl<T: PrimInt + Signed> Point2D<T> {
+    /// Move `n` steps in the given direction.
+    pub fn step(&self, dir: Direction, n: T) -> Self {
+        *self + dir.offset() * n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning() {
+        assert_eq!(North.turn_right(), East);
+        assert_eq!(North.turn_left(), West);
+        assert_eq!(NorthEast.turn_left(), NorthWest);
+        assert_eq!(West.turn_right_45(), NorthWest);
+        assert_eq!(SouthWest.opposite(), NorthEast);
+        for dir in Direction::ALL.iter() {
+            assert_eq!(dir.turn_left().turn_right(), *dir);
+            assert_eq!(dir.opposite().offset::<i32>() + dir.offset(), Point2D(0, 0));
+        }
+    }
+
+    #[test]
+    fn parse_and_step() {
+        let moves: Vec<Direction> = "U R D L N E S W"
+            .split(' ')
+            .map(|d| d.parse().unwrap())
+            .collect();
+        assert_eq!(moves[..4], moves[4..]);
+        assert_eq!(Direction::try_from('r'), Ok(East));
+        assert!("X".parse::<Direction>().is_err());
+
+        let start: Point2D<isize> = Point2D(1, 1);
+        let end = moves
+            .iter()
+            .zip(1..)
+            .fold(start, |p, (&dir, n)| p.step(dir, n));
+        assert_eq!(end, Point2D(-3, 5));
+    }
+}
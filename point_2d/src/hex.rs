@@ -0,0 +1,213 @@
+use crate::Point2D;
+use num::{PrimInt, Signed};
+use std::{error::Error, fmt, ops::Add};
+
+/// A cell on a hexagonal grid in axial coordinates `(q, r)`. The implied
+/// third cube coordinate is `s = -q - r`.
+///
+/// As with `Point2D`, r grows downwards, so "clockwise" is as seen on screen.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HexPoint<T> {
+    pub q: T,
+    pub r: T,
+}
+
+impl<T: PrimInt + Signed> HexPoint<T> {
+    pub fn new(q: T, r: T) -> Self {
+        Self { q, r }
+    }
+
+    /// Build from cube coordinates, or `None` if they don't sum to zero.
+    pub fn from_cube(q: T, r: T, s: T) -> Option<Self> {
+        if (q + r + s).is_zero() {
+            Some(Self { q, r })
+        } else {
+            None
+        }
+    }
+
+    pub fn s(&self) -> T {
+        -self.q - self.r
+    }
+
+    pub fn cube(&self) -> (T, T, T) {
+        (self.q, self.r, self.s())
+    }
+
+    /// The axial coordinates as a plain point, for storing hexes in the other
+    /// grid types in this crate.
+    pub fn axial(&self) -> Point2D<T> {
+        Point2D(self.q, self.r)
+    }
+
+    /// The six unit offsets, clockwise starting from `+q`.
+    pub fn directions() -> [Self; 6] {
+        let one = T::one();
+        let zero = T::zero();
+        [
+            Self::new(one, zero),
+            Self::new(zero, one),
+            Self::new(-one, one),
+            Self::new(-one, zero),
+            Self::new(zero, -one),
+            Self::new(one, -one),
+        ]
+    }
+
+    pub fn neighbors(&self) -> impl Iterator<Item = Self> {
+        let center = *self;
+        IntoIterator::into_iter(Self::directions()).map(move |dir| center + dir)
+    }
+
+    /// The number of steps between two hexes.
+    pub fn distance(&self, other: &Self) -> T {
+        let (dq, dr, ds) = (self.q - other.q, self.r - other.r, self.s() - other.s());
+        dq.abs().max(dr.abs()).max(ds.abs())
+    }
+
+    /// Rotate 60° clockwise about the origin.
+    pub fn rotate_right(&self) -> Self {
+        Self::new(-self.r, -self.s())
+    }
+
+    /// Rotate 60° counterclockwise about the origin.
+    pub fn rotate_left(&self) -> Self {
+        Self::new(-self.s(), -self.q)
+    }
+
+    /// Rotate 60° clockwise about another hex.
+    pub fn rotate_right_around(&self, center: &Self) -> Self {
+        let relative = Self::new(self.q - center.q, self.r - center.r);
+        *center + relative.rotate_right()
+    }
+
+    /// Rotate 60° counterclockwise about another hex.
+    pub fn rotate_left_around(&self, center: &Self) -> Self {
+        let relative = Self::new(self.q - center.q, self.r - center.r);
+        *center + relative.rotate_left()
+    }
+
+    /// Follow a run of direction names such as `ne`, `s` or `w`.
+    pub fn walk<'a>(
+        &self,
+        layout: HexLayout,
+        steps: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Self, ParseHexDirectionError> {
+        steps
+            .into_iter()
+            .try_fold(*self, |hex, step| Ok(hex + layout.offset(step)?))
+    }
+}
+
+impl<T: Add<T, Output = T>> Add for HexPoint<T> {
+    type Output = HexPoint<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            q: self.q + rhs.q,
+            r: self.r + rhs.r,
+        }
+    }
+}
+
+/// How hexes are drawn, which decides what the compass direction names mean.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum HexLayout {
+    /// Hexes share edges above and below, giving `n`, `ne`, `se`, `s`, `sw`
+    /// and `nw` neighbors.
+    FlatTop,
+    /// Hexes share edges left and right, giving `e`, `ne`, `se`, `w`, `sw`
+    /// and `nw` neighbors.
+    PointyTop,
+}
+
+impl HexLayout {
+    /// The axial offset for a direction name such as `ne` or `w`.
+    pub fn offset<T: PrimInt + Signed>(
+        self,
+        name: &str,
+    ) -> Result<HexPoint<T>, ParseHexDirectionError> {
+        let one = T::one();
+        let zero = T::zero();
+        let (q, r) = match (self, name.trim().to_ascii_lowercase().as_str()) {
+            (HexLayout::FlatTop, "n") | (HexLayout::PointyTop, "nw") => (zero, -one),
+            (_, "ne") => (one, -one),
+            (HexLayout::FlatTop, "se") | (HexLayout::PointyTop, "e") => (one, zero),
+            (HexLayout::FlatTop, "s") | (HexLayout::PointyTop, "se") => (zero, one),
+            (_, "sw") => (-one, one),
+            (HexLayout::FlatTop, "nw") | (HexLayout::PointyTop, "w") => (-one, zero),
+            _ => return Err(ParseHexDirectionError(name.to_string())),
+        };
+        Ok(HexPoint::new(q, r))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseHexDirectionError(String);
+
+impl fmt::Display for ParseHexDirectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid hex direction {:?}", self.0)
+    }
+}
+
+impl Error for ParseHexDirectionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbors_and_distance() {
+        let origin: HexPoint<i32> = HexPoint::new(0, 0);
+        let neighbors: Vec<_> = origin.neighbors().collect();
+        assert_eq!(neighbors.len(), 6);
+        assert!(neighbors.iter().all(|n| n.distance(&origin) == 1));
+
+        let far = HexPoint::from_cube(3, -5, 2).unwrap();
+        assert_eq!(far.distance(&origin), 5);
+        assert_eq!(far.axial(), Point2D(3, -5));
+        assert!(HexPoint::from_cube(1, 1, 1).is_none());
+    }
+
+    #[test]
+    fn rotation() {
+        let hex = HexPoint::new(2, -1);
+        let mut rotated = hex;
+        for _ in 0..6 {
+            rotated = rotated.rotate_right();
+            assert_eq!(rotated.distance(&HexPoint::new(0, 0)), 2);
+        }
+        assert_eq!(rotated, hex);
+        assert_eq!(hex.rotate_right().rotate_left(), hex);
+        assert_eq!(HexPoint::new(1, 0).rotate_right(), HexPoint::new(0, 1));
+
+        let center = HexPoint::new(5, 5);
+        assert_eq!(
+            HexPoint::new(6, 5).rotate_right_around(&center),
+            HexPoint::new(5, 6)
+        );
+    }
+
+    #[test]
+    fn walking() {
+        let origin: HexPoint<i32> = HexPoint::new(0, 0);
+        // Flat-topped examples from AoC 2017 day 11
+        let path = "se,sw,se,sw,sw".split(',');
+        let end = origin.walk(HexLayout::FlatTop, path).unwrap();
+        assert_eq!(end.distance(&origin), 3);
+        let back = "ne,ne,s,s".split(',');
+        let end = origin.walk(HexLayout::FlatTop, back).unwrap();
+        assert_eq!(end, HexPoint::new(2, 0));
+
+        // Pointy-topped example from AoC 2020 day 24
+        let round_trip = vec!["nw", "w", "sw", "e", "e"];
+        assert_eq!(
+            origin.walk(HexLayout::PointyTop, round_trip).unwrap(),
+            origin
+        );
+        assert!(origin.walk(HexLayout::PointyTop, vec!["n"]).is_err());
+        assert!(origin.walk(HexLayout::FlatTop, vec!["e"]).is_err());
+    }
+}
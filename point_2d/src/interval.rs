@@ -0,0 +1,276 @@
+use num::PrimInt;
+use std::iter::FromIterator;
+
+/// A non-empty inclusive range of integers, `start..=end`.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    /// Build the interval between two bounds, in any order.
+    pub fn new(a: T, b: T) -> Self {
+        Self {
+            start: a.min(b),
+            end: a.max(b),
+        }
+    }
+
+    pub fn single(value: T) -> Self {
+        Self::new(value, value)
+    }
+
+    /// The number of integers covered.
+    pub fn len(&self) -> T {
+        self.end - self.start + T::one()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        value >= self.start && value <= self.end
+    }
+
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        other.start >= self.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// Whether the two intervals overlap or sit end to end, so their union is
+    /// itself an interval.
+    pub fn touches(&self, other: &Self) -> bool {
+        let follows = |a: &Self, b: &Self| a.end.checked_add(&T::one()) == Some(b.start);
+        self.overlaps(other) || follows(self, other) || follows(other, self)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if self.overlaps(other) {
+            Some(Self {
+                start: self.start.max(other.start),
+                end: self.end.min(other.end),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// The parts of this interval not covered by `other`: none, one or two
+    /// pieces.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+
+        let mut pieces = vec![];
+        if other.start > self.start {
+            pieces.push(Self::new(self.start, other.start - T::one()));
+        }
+        if other.end < self.end {
+            pieces.push(Self::new(other.end + T::one(), self.end));
+        }
+        pieces
+    }
+
+    pub fn iter(&self) -> num::iter::RangeInclusive<T> {
+        num::range_inclusive(self.start, self.end)
+    }
+}
+
+/// A set of integers stored as sorted, disjoint intervals. Touching intervals
+/// are merged as they're added, so each run of consecutive values is a single
+/// interval.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { intervals: vec![] }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of integers in the set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |acc, interval| acc + interval.len())
+    }
+
+    /// The merged intervals, in ascending order.
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let idx = self
+            .intervals
+            .partition_point(|interval| interval.end < value);
+        self.intervals
+            .get(idx)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn contains_interval(&self, other: &Interval<T>) -> bool {
+        let idx = self
+            .intervals
+            .partition_point(|interval| interval.end < other.start);
+        self.intervals
+            .get(idx)
+            .is_some_and(|interval| interval.contains_interval(other))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let first = self.intervals.partition_point(|existing| {
+            existing.end < interval.start && !existing.touches(&interval)
+        });
+        let mut merged = interval;
+        let mut last = first;
+        while let Some(existing) = self.intervals.get(last) {
+            if !existing.touches(&merged) {
+                break;
+            }
+            merged = Interval::new(
+                merged.start.min(existing.start),
+                merged.end.max(existing.end),
+            );
+            last += 1;
+        }
+        self.intervals.splice(first..last, std::iter::once(merged));
+    }
+
+    pub fn remove(&mut self, interval: &Interval<T>) {
+        self.intervals = self
+            .intervals
+            .iter()
+            .flat_map(|existing| existing.difference(interval))
+            .collect();
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for interval in other.intervals.iter() {
+            result.insert(*interval);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = vec![];
+        let (mut a, mut b) = (
+            self.intervals.iter().peekable(),
+            other.intervals.iter().peekable(),
+        );
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            if let Some(overlap) = x.intersection(y) {
+                intervals.push(overlap);
+            }
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self { intervals }
+    }
+
+    /// The values in this set that aren't in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for interval in other.intervals.iter() {
+            result.remove(interval);
+        }
+        result
+    }
+
+    /// Every value in the set, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.intervals.iter().flat_map(|interval| interval.iter())
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intervals() {
+        let a = Interval::new(5, 1);
+        assert_eq!((a.start, a.end, a.len()), (1, 5, 5));
+        assert!(a.contains(3) && !a.contains(6));
+        assert!(a.touches(&Interval::new(6, 9)) && !a.overlaps(&Interval::new(6, 9)));
+        assert_eq!(
+            a.intersection(&Interval::new(4, 8)),
+            Some(Interval::new(4, 5))
+        );
+        assert_eq!(
+            a.difference(&Interval::single(3)),
+            vec![Interval::new(1, 2), Interval::new(4, 5)]
+        );
+        assert!(a.difference(&Interval::new(0, 9)).is_empty());
+        assert!(!Interval::single(u8::MAX).touches(&Interval::single(0)));
+    }
+
+    #[test]
+    fn merging_sets() {
+        let mut set: IntervalSet<i32> = vec![
+            Interval::new(10, 12),
+            Interval::new(1, 3),
+            Interval::new(5, 6),
+        ]
+        .into_iter()
+        .collect();
+        set.insert(Interval::new(4, 4));
+        assert_eq!(
+            set.intervals(),
+            &[Interval::new(1, 6), Interval::new(10, 12)]
+        );
+        assert_eq!(set.len(), 9);
+        assert!(set.contains(11) && !set.contains(8));
+        assert!(set.contains_interval(&Interval::new(2, 5)));
+        assert!(!set.contains_interval(&Interval::new(5, 10)));
+
+        set.remove(&Interval::new(3, 10));
+        assert_eq!(
+            set.intervals(),
+            &[Interval::new(1, 2), Interval::new(11, 12)]
+        );
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![1, 2, 11, 12]);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: IntervalSet<i64> = vec![Interval::new(0, 10), Interval::new(20, 30)]
+            .into_iter()
+            .collect();
+        let b: IntervalSet<i64> = vec![Interval::new(5, 25)].into_iter().collect();
+
+        assert_eq!(a.union(&b).intervals(), &[Interval::new(0, 30)]);
+        assert_eq!(
+            a.intersection(&b).intervals(),
+            &[Interval::new(5, 10), Interval::new(20, 25)]
+        );
+        assert_eq!(
+            a.difference(&b).intervals(),
+            &[Interval::new(0, 4), Interval::new(26, 30)]
+        );
+        assert_eq!(b.difference(&a).intervals(), &[Interval::new(11, 19)]);
+    }
+}
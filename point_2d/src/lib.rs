@@ -1,8 +1,31 @@
 use itertools::iproduct;
 use num::{range_inclusive, PrimInt, Signed};
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
-
-#[derive(Copy, Clone, Eq, Hash, PartialEq)]
+use std::{
+    cmp::Ordering,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+mod conversion;
+mod direction;
+mod hex;
+mod interval;
+mod point_map;
+pub mod polygon;
+mod rect;
+mod segment;
+mod text;
+
+pub use conversion::PointConversionError;
+pub use direction::{Direction, ParseDirectionError};
+pub use hex::{HexLayout, HexPoint, ParseHexDirectionError};
+pub use interval::{Interval, IntervalSet};
+pub use point_map::{ParseArtError, PointMap, PointSet, YAxis};
+pub use rect::Rect;
+pub use segment::{orientation, Segment, SegmentPoints};
+pub use text::ParsePointError;
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point2D<T>(pub T, pub T);
 
 impl<T: Add<T, Output = T>> Add for Point2D<T> {
@@ -137,6 +160,43 @@ impl<T: Div<T, Output = T> + Copy> DivAssign<T> for Point2D<T> {
     }
 }
 
+impl<T: Neg<Output = T>> Neg for Point2D<T> {
+    type Output = Point2D<T>;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0, -self.1)
+    }
+}
+
+impl<T: Neg<Output = T> + Copy> Neg for &Point2D<T> {
+    type Output = Point2D<T>;
+
+    fn neg(self) -> Self::Output {
+        Point2D(-self.0, -self.1)
+    }
+}
+
+/// Points are ordered in reading order: by row (y) first, then by column (x).
+impl<T: Ord> Ord for Point2D<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.1.cmp(&other.1).then_with(|| self.0.cmp(&other.0))
+    }
+}
+
+impl<T: Ord> PartialOrd for Point2D<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Point2D<T> {
+    /// Compare by column (x) first, then by row (y), for use with `sort_by`
+    /// and friends when reading order isn't wanted.
+    pub fn cmp_column_major(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0).then_with(|| self.1.cmp(&other.1))
+    }
+}
+
 impl<T: PrimInt + Signed> Point2D<T> {
     /// Given the point is part of a discrete grid, find the adjacent points.
     /// "Adjacent" here is considered to be the points in both the cardinal and
@@ -151,4 +211,65 @@ impl<T: PrimInt + Signed> Point2D<T> {
             .map(|ct| *self + ct)
             .collect()
     }
+
+    /// Wrap the point onto a torus of the given size, so each coordinate ends
+    /// up in `0..size`, including for negative coordinates.
+    pub fn rem_euclid(&self, size: &Self) -> Self {
+        let wrap = |n: T, size: T| {
+            let r = n % size;
+            if r < T::zero() {
+                r + size
+            } else {
+                r
+            }
+        };
+        Self(wrap(self.0, size.0), wrap(self.1, size.1))
+    }
+
+    /// The z component of the cross product of the two points as vectors.
+    pub fn cross(&self, other: &Self) -> T {
+        self.0 * other.1 - self.1 * other.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn ordering() {
+        let points: BTreeSet<_> = vec![Point2D(2, 1), Point2D(5, 0), Point2D(0, 1), Point2D(9, -1)]
+            .into_iter()
+            .collect();
+        let reading: Vec<_> = points.iter().copied().collect();
+        assert_eq!(
+            reading,
+            vec![Point2D(9, -1), Point2D(5, 0), Point2D(0, 1), Point2D(2, 1)]
+        );
+
+        let mut columns = reading;
+        columns.sort_by(Point2D::cmp_column_major);
+        assert_eq!(
+            columns,
+            vec![Point2D(0, 1), Point2D(2, 1), Point2D(5, 0), Point2D(9, -1)]
+        );
+    }
+
+    #[test]
+    fn negate_and_wrap() {
+        assert_eq!(-Point2D(3, -4), Point2D(-3, 4));
+        assert_eq!(-&Point2D(0, 1), Point2D(0, -1));
+
+        let size = Point2D(10, 5);
+        assert_eq!(Point2D(-1, 7).rem_euclid(&size), Point2D(9, 2));
+        assert_eq!(Point2D(-10, -15).rem_euclid(&size), Point2D(0, 0));
+        assert_eq!(Point2D(23, 4).rem_euclid(&size), Point2D(3, 4));
+
+        // Sizes over half the type's range mustn't overflow
+        assert_eq!(
+            Point2D(99i8, -1).rem_euclid(&Point2D(100, 127)),
+            Point2D(99, 126)
+        );
+    }
 }
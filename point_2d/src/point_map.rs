@@ -0,0 +1,312 @@
+use crate::{Point2D, Rect};
+use num::{range_inclusive, PrimInt};
+use std::{
+    collections::{hash_map, BTreeMap, HashMap},
+    error::Error,
+    fmt,
+    hash::Hash,
+    iter::FromIterator,
+    str::FromStr,
+};
+
+/// Which way y grows when laying points out as text.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum YAxis {
+    /// The first line of text is the smallest y, as in most puzzle inputs.
+    Down,
+    /// The last line of text is the smallest y, as on a plotted graph.
+    Up,
+}
+
+/// A sparse grid of values keyed by point, which keeps track of the bounding
+/// box of everything in it.
+#[derive(Clone, Debug)]
+pub struct PointMap<T, V> {
+    points: HashMap<Point2D<T>, V>,
+    // How many points sit in each column and row, so the bounds only shrink
+    // once the last point on an edge goes
+    columns: BTreeMap<T, usize>,
+    rows: BTreeMap<T, usize>,
+}
+
+fn count_in<T: Ord>(counts: &mut BTreeMap<T, usize>, key: T) {
+    *counts.entry(key).or_insert(0) += 1;
+}
+
+fn count_out<T: Ord>(counts: &mut BTreeMap<T, usize>, key: T) {
+    if let Some(count) = counts.get_mut(&key) {
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(&key);
+        }
+    }
+}
+
+/// A sparse set of points, such as the dots making up ASCII art.
+pub type PointSet<T> = PointMap<T, ()>;
+
+impl<T: PrimInt + Hash, V> PointMap<T, V> {
+    pub fn new() -> Self {
+        Self {
+            points: HashMap::new(),
+            columns: BTreeMap::new(),
+            rows: BTreeMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The smallest rectangle covering every point, or `None` when empty.
+    pub fn bounds(&self) -> Option<Rect<T>> {
+        let (&min_x, _) = self.columns.first_key_value()?;
+        let (&max_x, _) = self.columns.last_key_value()?;
+        let (&min_y, _) = self.rows.first_key_value()?;
+        let (&max_y, _) = self.rows.last_key_value()?;
+        Some(Rect::new(Point2D(min_x, min_y), Point2D(max_x, max_y)))
+    }
+
+    pub fn contains(&self, point: &Point2D<T>) -> bool {
+        self.points.contains_key(point)
+    }
+
+    pub fn get(&self, point: &Point2D<T>) -> Option<&V> {
+        self.points.get(point)
+    }
+
+    pub fn get_mut(&mut self, point: &Point2D<T>) -> Option<&mut V> {
+        self.points.get_mut(point)
+    }
+
+    pub fn insert(&mut self, point: Point2D<T>, value: V) -> Option<V> {
+        let replaced = self.points.insert(point, value);
+        if replaced.is_none() {
+            count_in(&mut self.columns, point.0);
+            count_in(&mut self.rows, point.1);
+        }
+        replaced
+    }
+
+    pub fn remove(&mut self, point: &Point2D<T>) -> Option<V> {
+        let removed = self.points.remove(point);
+        if removed.is_some() {
+            count_out(&mut self.columns, point.0);
+            count_out(&mut self.rows, point.1);
+        }
+        removed
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, Point2D<T>, V> {
+        self.points.iter()
+    }
+
+    pub fn points(&self) -> hash_map::Keys<'_, Point2D<T>, V> {
+        self.points.keys()
+    }
+
+    /// Render the cells of `region` as text, one line per row with a trailing
+    /// newline, using `glyph` to pick the character for each cell.
+    pub fn render_region(
+        &self,
+        region: &Rect<T>,
+        y_axis: YAxis,
+        glyph: impl Fn(Option<&V>) -> char,
+    ) -> String {
+        let mut rows: Vec<T> = range_inclusive(region.min.1, region.max.1).collect();
+        if y_axis == YAxis::Up {
+            rows.reverse();
+        }
+
+        let mut output = String::new();
+        for y in rows {
+            for x in range_inclusive(region.min.0, region.max.0) {
+                output.push(glyph(self.get(&Point2D(x, y))));
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Render the bounding box of the map as text. An empty map renders as an
+    /// empty string.
+    pub fn render_with(&self, y_axis: YAxis, glyph: impl Fn(Option<&V>) -> char) -> String {
+        match self.bounds() {
+            Some(bounds) => self.render_region(&bounds, y_axis, glyph),
+            None => String::new(),
+        }
+    }
+
+    /// Build a map from text art, with the first character of the first
+    /// (`YAxis::Down`) or last (`YAxis::Up`) line at the origin. Cells for
+    /// which `cell` returns `None` are left empty. Fails if a filled cell lies
+    /// beyond the range of the coordinate type.
+    pub fn from_text(
+        text: &str,
+        y_axis: YAxis,
+        mut cell: impl FnMut(char) -> Option<V>,
+    ) -> Result<Self, ParseArtError> {
+        let mut lines: Vec<&str> = text.lines().collect();
+        if y_axis == YAxis::Up {
+            lines.reverse();
+        }
+
+        let mut map = Self::new();
+        for (y, line) in lines.into_iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                if let Some(value) = cell(c) {
+                    let point = match (T::from(x), T::from(y)) {
+                        (Some(x), Some(y)) => Point2D(x, y),
+                        _ => return Err(ParseArtError::OutOfRange { x, y }),
+                    };
+                    map.insert(point, value);
+                }
+            }
+        }
+        Ok(map)
+    }
+}
+
+impl<T: PrimInt + Hash> PointSet<T> {
+    /// Add a point, returning whether it was newly added.
+    pub fn mark(&mut self, point: Point2D<T>) -> bool {
+        self.insert(point, ()).is_none()
+    }
+
+    /// Render the bounding box of the set with `on` for points in the set and
+    /// `off` for the gaps between them.
+    pub fn render(&self, on: char, off: char, y_axis: YAxis) -> String {
+        self.render_with(y_axis, |cell| if cell.is_some() { on } else { off })
+    }
+}
+
+impl<T: PrimInt + Hash, V> Default for PointMap<T, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PrimInt + Hash, V: PartialEq> PartialEq for PointMap<T, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.points == other.points
+    }
+}
+
+impl<T: PrimInt + Hash, V: Eq> Eq for PointMap<T, V> {}
+
+impl<T: PrimInt + Hash, V> FromIterator<(Point2D<T>, V)> for PointMap<T, V> {
+    fn from_iter<I: IntoIterator<Item = (Point2D<T>, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (point, value) in iter {
+            map.insert(point, value);
+        }
+        map
+    }
+}
+
+impl<T: PrimInt + Hash> FromIterator<Point2D<T>> for PointSet<T> {
+    fn from_iter<I: IntoIterator<Item = Point2D<T>>>(iter: I) -> Self {
+        iter.into_iter().map(|point| (point, ())).collect()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseArtError {
+    UnexpectedChar(char),
+    /// A filled cell, counted from the origin line, that doesn't fit the
+    /// coordinate type.
+    OutOfRange {
+        x: usize,
+        y: usize,
+    },
+}
+
+impl fmt::Display for ParseArtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedChar(c) => write!(f, "unexpected character {:?} in text art", c),
+            Self::OutOfRange { x, y } => {
+                write!(
+                    f,
+                    "text art cell ({}, {}) is out of range for the coordinates",
+                    x, y
+                )
+            }
+        }
+    }
+}
+
+impl Error for ParseArtError {}
+
+impl<T: PrimInt + Hash> FromStr for PointSet<T> {
+    type Err = ParseArtError;
+
+    /// Parses `#`/`.` art with y growing downwards from the first line.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(bad) = s.chars().find(|c| !matches!(c, '#' | '.' | '\n' | '\r')) {
+            return Err(ParseArtError::UnexpectedChar(bad));
+        }
+        Self::from_text(s, YAxis::Down, |c| if c == '#' { Some(()) } else { None })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ART: &str = "#..\n.#.\n..#\n#..\n";
+
+    #[test]
+    fn bounds_tracking() {
+        let mut set: PointSet<i32> = vec![Point2D(-2, 1), Point2D(3, 0), Point2D(1, 5)]
+            .into_iter()
+            .collect();
+        assert_eq!(set.bounds(), Some(Rect::new(Point2D(-2, 0), Point2D(3, 5))));
+        assert!(!set.mark(Point2D(3, 0)));
+
+        set.remove(&Point2D(1, 5));
+        assert_eq!(set.bounds(), Some(Rect::new(Point2D(-2, 0), Point2D(3, 1))));
+        // An edge only moves in once its last point is gone
+        set.mark(Point2D(0, 1));
+        set.remove(&Point2D(-2, 1));
+        assert_eq!(set.bounds(), Some(Rect::new(Point2D(0, 0), Point2D(3, 1))));
+        set.mark(Point2D(-2, 1));
+        set.remove(&Point2D(0, 1));
+        set.remove(&Point2D(-2, 1));
+        set.remove(&Point2D(3, 0));
+        assert_eq!(set.bounds(), None);
+        assert_eq!(set.render('#', '.', YAxis::Down), "");
+    }
+
+    #[test]
+    fn render_and_parse() {
+        let set: PointSet<u16> = ART.parse().unwrap();
+        assert_eq!(set.len(), 4);
+        assert!(set.contains(&Point2D(0, 3)));
+        assert_eq!(set.render('#', '.', YAxis::Down), ART);
+        assert_eq!(set.render('█', ' ', YAxis::Up), "█  \n  █\n █ \n█  \n");
+        assert_eq!(
+            "#x".parse::<PointSet<u16>>(),
+            Err(ParseArtError::UnexpectedChar('x'))
+        );
+        assert_eq!(
+            "#".repeat(200).parse::<PointSet<i8>>(),
+            Err(ParseArtError::OutOfRange { x: 128, y: 0 })
+        );
+        // Empty cells past the range are fine
+        let wide = format!("#{}", ".".repeat(200));
+        assert_eq!(wide.parse::<PointSet<i8>>().map(|set| set.len()), Ok(1));
+
+        let digits: PointMap<i8, u32> =
+            PointMap::from_text("12\n.3", YAxis::Up, |c| c.to_digit(10)).unwrap();
+        assert_eq!(digits.get(&Point2D(1, 0)), Some(&3));
+        let rendered = digits.render_with(YAxis::Up, |d| {
+            d.and_then(|&d| std::char::from_digit(d, 10)).unwrap_or('?')
+        });
+        assert_eq!(rendered, "12\n?3\n");
+    }
+}
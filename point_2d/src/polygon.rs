@@ -0,0 +1,128 @@
+//! Geometry of simple polygons given as a slice of vertices in order. The
+//! last vertex joins back up to the first, so it shouldn't be repeated.
+
+use crate::{orientation, segment::gcd, Point2D, Segment};
+use num::{PrimInt, Signed};
+use std::cmp::Ordering;
+
+/// Where a point lies relative to a polygon.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Containment {
+    Inside,
+    OnBoundary,
+    Outside,
+}
+
+fn edges<T: Copy>(vertices: &[Point2D<T>]) -> impl Iterator<Item = (Point2D<T>, Point2D<T>)> + '_ {
+    vertices
+        .iter()
+        .copied()
+        .zip(vertices.iter().copied().cycle().skip(1))
+}
+
+/// Twice the enclosed area, by the shoelace formula. Doubling keeps the
+/// result exact, since lattice polygons can have half-integer areas.
+pub fn double_area<T: PrimInt + Signed>(vertices: &[Point2D<T>]) -> T {
+    edges(vertices)
+        .fold(T::zero(), |acc, (a, b)| acc + a.cross(&b))
+        .abs()
+}
+
+pub fn area<T: PrimInt + Signed>(vertices: &[Point2D<T>]) -> f64 {
+    double_area(vertices).to_f64().expect("Area out of range") / 2.0
+}
+
+/// The Euclidean length of the boundary.
+pub fn perimeter<T: PrimInt + Signed>(vertices: &[Point2D<T>]) -> f64 {
+    edges(vertices)
+        .map(|(a, b)| {
+            let Point2D(dx, dy) = b - a;
+            let dx = dx.to_f64().expect("Edge out of range");
+            let dy = dy.to_f64().expect("Edge out of range");
+            dx.hypot(dy)
+        })
+        .sum()
+}
+
+/// The number of lattice points on the boundary. For polygons traced along
+/// grid lines this is the same as the perimeter.
+pub fn boundary_points<T: PrimInt + Signed>(vertices: &[Point2D<T>]) -> T {
+    edges(vertices).fold(T::zero(), |acc, (a, b)| {
+        let Point2D(dx, dy) = b - a;
+        acc + gcd(dx.abs(), dy.abs())
+    })
+}
+
+/// The number of lattice points strictly inside the polygon, by Pick's
+/// theorem.
+pub fn interior_points<T: PrimInt + Signed>(vertices: &[Point2D<T>]) -> T {
+    let two = T::one() + T::one();
+    (double_area(vertices) - boundary_points(vertices) + two) / two
+}
+
+/// Test a point against the polygon using its winding number, so this works
+/// whichever way round the vertices are listed.
+pub fn contains<T: PrimInt + Signed>(vertices: &[Point2D<T>], point: &Point2D<T>) -> Containment {
+    let mut winding = 0;
+    for (a, b) in edges(vertices) {
+        if Segment::new(a, b).contains(point) {
+            return Containment::OnBoundary;
+        }
+
+        let side = orientation(a, b, *point);
+        if a.1 <= point.1 && b.1 > point.1 && side == Ordering::Greater {
+            winding += 1;
+        } else if b.1 <= point.1 && a.1 > point.1 && side == Ordering::Less {
+            winding -= 1;
+        }
+    }
+
+    if winding == 0 {
+        Containment::Outside
+    } else {
+        Containment::Inside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measurements() {
+        let square = [Point2D(0, 0), Point2D(4, 0), Point2D(4, 4), Point2D(0, 4)];
+        assert_eq!(double_area(&square), 32);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+        assert!((perimeter(&square) - 16.0).abs() < 1e-9);
+
+        let triangle = [Point2D(0i64, 0), Point2D(3, 1), Point2D(1, 2)];
+        assert!((area(&triangle) - 2.5).abs() < 1e-9);
+        assert_eq!(boundary_points(&triangle), 3);
+        assert_eq!(interior_points(&triangle), 2);
+    }
+
+    #[test]
+    fn point_in_polygon() {
+        // An L shape, listed clockwise on a y-down grid
+        let l_shape = [
+            Point2D(0, 0),
+            Point2D(2, 0),
+            Point2D(2, 2),
+            Point2D(4, 2),
+            Point2D(4, 4),
+            Point2D(0, 4),
+        ];
+        let mut reversed = l_shape;
+        reversed.reverse();
+
+        for shape in [l_shape, reversed].iter() {
+            assert_eq!(contains(shape, &Point2D(1, 1)), Containment::Inside);
+            assert_eq!(contains(shape, &Point2D(3, 3)), Containment::Inside);
+            assert_eq!(contains(shape, &Point2D(3, 1)), Containment::Outside);
+            assert_eq!(contains(shape, &Point2D(3, 2)), Containment::OnBoundary);
+            assert_eq!(contains(shape, &Point2D(0, 4)), Containment::OnBoundary);
+            assert_eq!(contains(shape, &Point2D(-1, 2)), Containment::Outside);
+        }
+    }
+}
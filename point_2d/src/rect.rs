@@ -0,0 +1,138 @@
+use crate::{Interval, Point2D};
+use itertools::iproduct;
+use num::{range_inclusive, PrimInt};
+
+/// An axis-aligned rectangle of grid cells. Both corners are inclusive, so a
+/// `Rect` always covers at least one point.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect<T> {
+    pub min: Point2D<T>,
+    pub max: Point2D<T>,
+}
+
+impl<T: PrimInt> Rect<T> {
+    /// Build the rectangle spanned by two opposite corners, in any order.
+    pub fn new(a: Point2D<T>, b: Point2D<T>) -> Self {
+        Self {
+            min: Point2D(a.0.min(b.0), a.1.min(b.1)),
+            max: Point2D(a.0.max(b.0), a.1.max(b.1)),
+        }
+    }
+
+    /// The smallest rectangle covering every point, or `None` if there are none.
+    pub fn bounding_box<'a>(points: impl IntoIterator<Item = &'a Point2D<T>>) -> Option<Self>
+    where
+        T: 'a,
+    {
+        points.into_iter().fold(None, |rect: Option<Self>, &point| {
+            Some(match rect {
+                Some(rect) => rect.expand_to(point),
+                None => Self::new(point, point),
+            })
+        })
+    }
+
+    /// The smallest rectangle covering both this one and the point.
+    pub fn expand_to(&self, point: Point2D<T>) -> Self {
+        Self {
+            min: Point2D(self.min.0.min(point.0), self.min.1.min(point.1)),
+            max: Point2D(self.max.0.max(point.0), self.max.1.max(point.1)),
+        }
+    }
+
+    pub fn width(&self) -> T {
+        self.max.0 - self.min.0 + T::one()
+    }
+
+    pub fn height(&self) -> T {
+        self.max.1 - self.min.1 + T::one()
+    }
+
+    /// The x coordinates covered by the rectangle.
+    pub fn x_interval(&self) -> Interval<T> {
+        Interval::new(self.min.0, self.max.0)
+    }
+
+    /// The y coordinates covered by the rectangle.
+    pub fn y_interval(&self) -> Interval<T> {
+        Interval::new(self.min.1, self.max.1)
+    }
+
+    pub fn area(&self) -> T {
+        self.width() * self.height()
+    }
+
+    pub fn contains(&self, point: &Point2D<T>) -> bool {
+        point.0 >= self.min.0
+            && point.0 <= self.max.0
+            && point.1 >= self.min.1
+            && point.1 <= self.max.1
+    }
+
+    /// The overlapping region of the two rectangles, if any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = Point2D(self.min.0.max(other.min.0), self.min.1.max(other.min.1));
+        let max = Point2D(self.max.0.min(other.max.0), self.max.1.min(other.max.1));
+        if min.0 <= max.0 && min.1 <= max.1 {
+            Some(Self { min, max })
+        } else {
+            None
+        }
+    }
+
+    /// The smallest rectangle covering both rectangles.
+    pub fn union(&self, other: &Self) -> Self {
+        self.expand_to(other.min).expand_to(other.max)
+    }
+
+    /// Every point in the rectangle in reading order: left to right along
+    /// each row, rows from smallest y to largest.
+    pub fn points(&self) -> impl Iterator<Item = Point2D<T>> {
+        iproduct!(
+            range_inclusive(self.min.1, self.max.1),
+            range_inclusive(self.min.0, self.max.0)
+        )
+        .map(|(y, x)| Point2D(x, y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dimensions_and_points() {
+        let rect: Rect<u16> = Rect::new(Point2D(3, 1), Point2D(1, 2));
+        assert_eq!((rect.min, rect.max), (Point2D(1, 1), Point2D(3, 2)));
+        assert_eq!((rect.width(), rect.height(), rect.area()), (3, 2, 6));
+        assert_eq!(rect.y_interval(), Interval::new(1, 2));
+        assert!(rect.contains(&Point2D(2, 2)) && !rect.contains(&Point2D(0, 2)));
+
+        let points: Vec<_> = rect.points().collect();
+        assert_eq!(points.len(), 6);
+        assert_eq!(points[..2], [Point2D(1, 1), Point2D(2, 1)]);
+        assert_eq!(points[3], Point2D(1, 2));
+    }
+
+    #[test]
+    fn combining() {
+        let a = Rect::new(Point2D(0, 0), Point2D(4, 4));
+        let b = Rect::new(Point2D(3, -2), Point2D(6, 1));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Rect::new(Point2D(3, 0), Point2D(4, 1)))
+        );
+        assert_eq!(a.union(&b), Rect::new(Point2D(0, -2), Point2D(6, 4)));
+        assert!(a
+            .intersection(&Rect::new(Point2D(5, 5), Point2D(5, 5)))
+            .is_none());
+
+        let dots = vec![Point2D(2, 7), Point2D(-1, 3), Point2D(4, 4)];
+        assert_eq!(
+            Rect::bounding_box(&dots),
+            Some(Rect::new(Point2D(-1, 3), Point2D(4, 7)))
+        );
+        assert!(Rect::<i32>::bounding_box(&vec![]).is_none());
+    }
+}
@@ -0,0 +1,215 @@
+use crate::Point2D;
+use num::{PrimInt, Signed};
+use std::cmp::Ordering;
+
+/// A closed line segment between two points on an integer grid.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment<T> {
+    pub start: Point2D<T>,
+    pub end: Point2D<T>,
+}
+
+pub(crate) fn gcd<T: PrimInt>(a: T, b: T) -> T {
+    if b.is_zero() {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Which side of the line through `a` and `b` the point `c` falls on.
+/// `Greater` is counterclockwise with the y axis pointing up (clockwise on a
+/// y-down grid), `Less` is the other way round and `Equal` is collinear.
+pub fn orientation<T: PrimInt + Signed>(a: Point2D<T>, b: Point2D<T>, c: Point2D<T>) -> Ordering {
+    let (a, b, c) = (widen(a), widen(b), widen(c));
+    (b - a).cross(&(c - a)).cmp(&0)
+}
+
+/// Cross products grow with the square of the coordinates, and intersections
+/// with the cube, so they're worked out in `i128`. This is exact for
+/// coordinates of up to 32 bits, and 64 bits where only cross products are
+/// needed.
+fn widen<T: PrimInt + Signed>(point: Point2D<T>) -> Point2D<i128> {
+    let convert = |n: T| n.to_i128().expect("Coordinate out of range for i128");
+    Point2D(convert(point.0), convert(point.1))
+}
+
+impl<T: PrimInt + Signed> Segment<T> {
+    pub fn new(start: Point2D<T>, end: Point2D<T>) -> Self {
+        Self { start, end }
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.start.1 == self.end.1
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.start.0 == self.end.0
+    }
+
+    pub fn is_axis_aligned(&self) -> bool {
+        self.is_horizontal() || self.is_vertical()
+    }
+
+    /// Whether the segment runs at exactly 45° to the axes.
+    pub fn is_diagonal(&self) -> bool {
+        let Point2D(dx, dy) = self.end - self.start;
+        !dx.is_zero() && dx.abs() == dy.abs()
+    }
+
+    pub fn is_point(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// The smallest lattice step along the segment, or zero for a single point.
+    fn unit_step(&self) -> (Point2D<T>, T) {
+        let delta = self.end - self.start;
+        let steps = gcd(delta.0.abs(), delta.1.abs());
+        if steps.is_zero() {
+            (delta, steps)
+        } else {
+            (delta / steps, steps)
+        }
+    }
+
+    /// Iterate over every lattice point the segment passes through, from
+    /// `start` to `end` inclusive. Works for any slope, not just multiples of 45°.
+    pub fn points(&self) -> SegmentPoints<T> {
+        let (step, steps) = self.unit_step();
+        SegmentPoints {
+            next: self.start,
+            step,
+            remaining: Some(steps),
+        }
+    }
+
+    pub fn contains(&self, point: &Point2D<T>) -> bool {
+        orientation(self.start, self.end, *point) == Ordering::Equal
+            && point.0 >= self.start.0.min(self.end.0)
+            && point.0 <= self.start.0.max(self.end.0)
+            && point.1 >= self.start.1.min(self.end.1)
+            && point.1 <= self.start.1.max(self.end.1)
+    }
+
+    /// Whether the two segments share any point, lattice or otherwise.
+    pub fn intersects(&self, other: &Self) -> bool {
+        let o1 = orientation(self.start, self.end, other.start);
+        let o2 = orientation(self.start, self.end, other.end);
+        let o3 = orientation(other.start, other.end, self.start);
+        let o4 = orientation(other.start, other.end, self.end);
+
+        (o1 != o2 && o3 != o4)
+            || self.contains(&other.start)
+            || self.contains(&other.end)
+            || other.contains(&self.start)
+            || other.contains(&self.end)
+    }
+
+    /// The lattice points shared by both segments. Crossing segments yield at
+    /// most one point, and none if they cross between lattice points;
+    /// overlapping collinear segments yield every shared point in order.
+    pub fn intersection(&self, other: &Self) -> Vec<Point2D<T>> {
+        if !self.intersects(other) {
+            return vec![];
+        }
+
+        let start = widen(self.start);
+        let r = widen(self.end) - start;
+        let s = widen(other.end) - widen(other.start);
+        let denominator = r.cross(&s);
+        if denominator == 0 {
+            return self.points().filter(|p| other.contains(p)).collect();
+        }
+
+        // start + r * (numerator / denominator), kept exact in integers
+        let numerator = (widen(other.start) - start).cross(&s);
+        let scaled = start * denominator + r * numerator;
+        if scaled.0 % denominator == 0 && scaled.1 % denominator == 0 {
+            // The point lies on this segment, so it fits back in `T`
+            let Point2D(x, y) = scaled / denominator;
+            let narrow = |n: i128| T::from(n).expect("Intersection out of range");
+            vec![Point2D(narrow(x), narrow(y))]
+        } else {
+            vec![]
+        }
+    }
+}
+
+pub struct SegmentPoints<T> {
+    next: Point2D<T>,
+    step: Point2D<T>,
+    remaining: Option<T>,
+}
+
+impl<T: PrimInt + Signed> Iterator for SegmentPoints<T> {
+    type Item = Point2D<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining = self.remaining?;
+        let current = self.next;
+        if remaining.is_zero() {
+            self.remaining = None;
+        } else {
+            self.remaining = Some(remaining - T::one());
+            self.next += self.step;
+        }
+        Some(current)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining.and_then(|r| r.to_usize()).map(|r| r + 1);
+        (len.unwrap_or(0), len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rasterize_any_slope() {
+        let steep = Segment::new(Point2D(0, 0), Point2D(2, 6));
+        let points: Vec<_> = steep.points().collect();
+        assert_eq!(points, vec![Point2D(0, 0), Point2D(1, 3), Point2D(2, 6)]);
+
+        let diagonal = Segment::new(Point2D(9, 7), Point2D(7, 9));
+        assert!(diagonal.is_diagonal() && !diagonal.is_axis_aligned());
+        assert_eq!(diagonal.points().count(), 3);
+
+        let single = Segment::new(Point2D(4, 4), Point2D(4, 4));
+        assert!(single.is_point() && single.is_horizontal() && single.is_vertical());
+        assert_eq!(single.points().count(), 1);
+    }
+
+    #[test]
+    fn intersections() {
+        let a = Segment::new(Point2D(0, 0), Point2D(4, 4));
+        let b = Segment::new(Point2D(0, 4), Point2D(4, 0));
+        assert_eq!(a.intersection(&b), vec![Point2D(2, 2)]);
+
+        // Crosses at (0.5, 0.5)
+        let c = Segment::new(Point2D(0, 1), Point2D(1, 0));
+        assert!(a.intersects(&c));
+        assert!(a.intersection(&c).is_empty());
+
+        let overlap = Segment::new(Point2D(3, 3), Point2D(6, 6));
+        assert_eq!(a.intersection(&overlap), vec![Point2D(3, 3), Point2D(4, 4)]);
+
+        // Intermediate products that don't fit the coordinate type
+        let wide = Segment::new(Point2D(0i8, 0), Point2D(100, 100));
+        let cross = Segment::new(Point2D(0i8, 100), Point2D(100, 0));
+        assert_eq!(wide.intersection(&cross), vec![Point2D(50, 50)]);
+        let d = Segment::new(Point2D(1200i32, 0), Point2D(0, 1200));
+        let e = Segment::new(Point2D(1200i32, 1200), Point2D(0, 1));
+        assert!(d.intersects(&e));
+        assert!(d.intersection(&e).is_empty());
+
+        let apart = Segment::new(Point2D(5, 0), Point2D(9, 0));
+        assert!(!a.intersects(&apart));
+        assert_eq!(
+            orientation(Point2D(0, 0), Point2D(1, 0), Point2D(0, 1)),
+            Ordering::Greater
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use crate::Point2D;
+use std::{error::Error, fmt, str::FromStr};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParsePointError(String);
+
+impl fmt::Display for ParsePointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid point {:?}", self.0)
+    }
+}
+
+impl Error for ParsePointError {}
+
+impl<T: FromStr> FromStr for Point2D<T> {
+    type Err = ParsePointError;
+
+    /// Parses `x,y`, allowing whitespace around either coordinate and an
+    /// optional pair of surrounding parentheses, e.g. `( 3, -4 )`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParsePointError(s.to_string());
+        let trimmed = s.trim();
+        let inner = match trimmed.strip_prefix('(') {
+            Some(rest) => rest.strip_suffix(')').ok_or_else(err)?,
+            None => trimmed,
+        };
+        let (x, y) = inner.split_once(',').ok_or_else(err)?;
+
+        Ok(Point2D(
+            x.trim().parse().map_err(|_| err())?,
+            y.trim().parse().map_err(|_| err())?,
+        ))
+    }
+}
+
+/// Formats as `x,y`, the same shape puzzle inputs use, so it round-trips
+/// through `FromStr`.
+impl<T: fmt::Display> fmt::Display for Point2D<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.0, self.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!("3,4".parse(), Ok(Point2D(3, 4)));
+        assert_eq!(" ( -3 , 4) ".parse(), Ok(Point2D(-3, 4)));
+        assert!("(3,4".parse::<Point2D<i32>>().is_err());
+        assert!("3;4".parse::<Point2D<i32>>().is_err());
+        assert!("3,-4".parse::<Point2D<u16>>().is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let point = Point2D(-12isize, 7);
+        assert_eq!(point.to_string(), "-12,7");
+        assert_eq!(point.to_string().parse(), Ok(point));
+        assert_eq!(format!("{:?}", point), "Point2D(-12, 7)");
+    }
+}